@@ -0,0 +1,252 @@
+//! Print the events deposited by the runtime, one json object per line.
+//!
+//! Events are read by subscribing to `System::Events`, which holds the events of the latest
+//! block. The node sends its current value as soon as the subscription is made, so the events of
+//! the best block are printed first. Event fields are output as a json array, in the order the
+//! module declares them.
+
+use crate::json::Json;
+use crate::query::block;
+use crate::{BlockHash, ChainClient};
+use futures::compat::Stream01CompatExt;
+use futures::stream::TryStreamExt;
+use jsonrpc_client_transports::RpcError;
+use node_template_runtime::{Event, Runtime};
+use parity_scale_codec::DecodeAll;
+use serde::Serialize;
+use serde_json::json;
+use sr_primitives::weights::DispatchInfo;
+use sr_primitives::DispatchError;
+use srml_support::storage::generator::StorageValue;
+use srml_system::{EventRecord, Phase};
+use structopt::StructOpt;
+use substrate_primitives::Bytes;
+use substrate_primitives_storage::StorageKey;
+use substrate_rpc_api::state::StateClient;
+
+#[derive(StructOpt, Debug)]
+pub struct Events {
+    /// Only output events of this module, such as "erc20" or "voting".
+    #[structopt(long)]
+    module: Option<String>,
+    /// Only output events with this name, such as "Transfer".
+    #[structopt(long)]
+    event: Option<String>,
+}
+
+impl Events {
+    /// Runs until the node closes the subscription.
+    pub async fn run(
+        self,
+        state: StateClient<BlockHash>,
+        chain: ChainClient,
+    ) -> Result<(), RpcError> {
+        let key = StorageKey(srml_system::Events::<Runtime>::storage_value_final_key().to_vec());
+        let mut change_sets = state.subscribe_storage(Some(vec![key.clone()]))?.compat();
+        while let Some(change_set) = change_sets.try_next().await? {
+            let changes = change_set
+                .changes
+                .into_iter()
+                .filter(|(changed, _)| *changed == key);
+            for (_, raw_records) in changes {
+                let records = match raw_records {
+                    Some(raw) => Vec::<EventRecord<Event, BlockHash>>::decode_all(&raw.0)
+                        .map_err(|e| RpcError::Other(e.into()))?,
+                    None => continue,
+                };
+                let block = block(&chain, change_set.block).await?;
+                for record in records {
+                    if let Some(line) = self.to_json(&record, &block) {
+                        println!("{}", line.as_str());
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Describe an event as json, or None if it is filtered out.
+    /// Panics if in-memory serialization fails.
+    fn to_json(&self, record: &EventRecord<Event, BlockHash>, block: &Json) -> Option<Json> {
+        let (module, (name, fields)) = describe(&record.event);
+        let matches = |filter: &Option<String>, name: &str| {
+            filter
+                .as_ref()
+                .map_or(true, |filter| filter.eq_ignore_ascii_case(name))
+        };
+        if !matches(&self.module, module) || !matches(&self.event, name) {
+            return None;
+        }
+        let phase = match record.phase {
+            Phase::ApplyExtrinsic(index) => json!({ "ApplyExtrinsic": index }),
+            Phase::Finalization => json!("Finalization"),
+        };
+        Some(Json::object(vec![
+            ("block", block.clone()),
+            ("phase", Json::create(&phase).unwrap()),
+            ("module", Json::create(&module).unwrap()),
+            ("event", Json::create(&name).unwrap()),
+            ("fields", fields),
+        ]))
+    }
+}
+
+/// Name of the module that deposited the event, the name of the event and its fields.
+/// Panics if in-memory serialization fails.
+fn describe(event: &Event) -> (&'static str, (&'static str, Json)) {
+    use erc20::RawEvent as Erc20;
+    use srml_balances::RawEvent as Balances;
+    use srml_grandpa::Event as Grandpa;
+    use srml_sudo::RawEvent as Sudo;
+    use srml_system::Event as System;
+    use voting::RawEvent as Voting;
+
+    match event {
+        Event::system(event) => (
+            "system",
+            match event {
+                System::ExtrinsicSuccess(info) => {
+                    ("ExtrinsicSuccess", fields(&(dispatch_info(info),)))
+                }
+                System::ExtrinsicFailed(error, info) => (
+                    "ExtrinsicFailed",
+                    fields(&(dispatch_error(error), dispatch_info(info))),
+                ),
+            },
+        ),
+        Event::grandpa(event) => (
+            "grandpa",
+            match event {
+                Grandpa::NewAuthorities(authorities) => {
+                    let authorities: Vec<(Bytes, u64)> = authorities
+                        .iter()
+                        .map(|(id, weight)| (Bytes(id.as_ref().to_vec()), *weight))
+                        .collect();
+                    ("NewAuthorities", fields(&(authorities,)))
+                }
+                Grandpa::Paused => ("Paused", fields(&[(); 0])),
+                Grandpa::Resumed => ("Resumed", fields(&[(); 0])),
+            },
+        ),
+        Event::balances(event) => (
+            "balances",
+            match event {
+                Balances::NewAccount(account, balance) => {
+                    ("NewAccount", fields(&(account, balance)))
+                }
+                Balances::ReapedAccount(account) => ("ReapedAccount", fields(&(account,))),
+                Balances::Transfer(from, to, value, fee) => {
+                    ("Transfer", fields(&(from, to, value, fee)))
+                }
+                // marks the instance of the module, it is never deposited
+                Balances::PhantomData(_) => unreachable!(),
+            },
+        ),
+        Event::sudo(event) => (
+            "sudo",
+            match event {
+                Sudo::Sudid(ok) => ("Sudid", fields(&(ok,))),
+                Sudo::KeyChanged(old) => ("KeyChanged", fields(&(old,))),
+                Sudo::SudoAsDone(ok) => ("SudoAsDone", fields(&(ok,))),
+            },
+        ),
+        Event::erc20(event) => (
+            "erc20",
+            match event {
+                Erc20::Transfer(token, from, to, value) => {
+                    ("Transfer", fields(&(token, from, to, value)))
+                }
+                Erc20::Approval(token, owner, spender, value) => {
+                    ("Approval", fields(&(token, owner, spender, value)))
+                }
+                Erc20::Burn(token, owner, value) => ("Burn", fields(&(token, owner, value))),
+            },
+        ),
+        Event::voting(event) => (
+            "voting",
+            match event {
+                Voting::VoteCreated(id, creator, vote_type) => {
+                    ("VoteCreated", fields(&(id, creator, vote_type)))
+                }
+                Voting::VoteAdvanced(id, old, new) => ("VoteAdvanced", fields(&(id, old, new))),
+                Voting::VoteCommitted(id, voter) => ("VoteCommitted", fields(&(id, voter))),
+                Voting::VoteRevealed(id, voter, outcomes) => {
+                    ("VoteRevealed", fields(&(id, voter, outcomes)))
+                }
+            },
+        ),
+    }
+}
+
+/// Event fields, given as a tuple or array so they serialize as a json array.
+fn fields<T: Serialize>(fields: &T) -> Json {
+    Json::create(fields).unwrap()
+}
+
+fn dispatch_info(info: &DispatchInfo) -> serde_json::Value {
+    json!({ "weight": info.weight, "class": format!("{:?}", info.class) })
+}
+
+fn dispatch_error(error: &DispatchError) -> serde_json::Value {
+    json!({ "module": error.module, "error": error.error, "message": error.message })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use substrate_warmup_common::DevAccount;
+
+    fn transfer() -> EventRecord<Event, BlockHash> {
+        EventRecord {
+            phase: Phase::ApplyExtrinsic(1),
+            event: Event::erc20(erc20::RawEvent::Transfer(
+                0,
+                DevAccount::Alice.account_id(),
+                DevAccount::Bob.account_id(),
+                10,
+            )),
+            topics: vec![],
+        }
+    }
+
+    #[test]
+    fn t_to_json() {
+        let block = Json::create(&"block").unwrap();
+        let events = |module: Option<&str>, event: Option<&str>| Events {
+            module: module.map(str::to_string),
+            event: event.map(str::to_string),
+        };
+
+        let json = events(None, None).to_json(&transfer(), &block).unwrap();
+        let json: serde_json::Value = serde_json::from_str(json.as_str()).unwrap();
+        assert_eq!(json["block"], "block");
+        assert_eq!(json["phase"], json!({ "ApplyExtrinsic": 1 }));
+        assert_eq!(json["module"], "erc20");
+        assert_eq!(json["event"], "Transfer");
+        assert_eq!(
+            json["fields"],
+            json!([
+                0,
+                DevAccount::Alice.account_id(),
+                DevAccount::Bob.account_id(),
+                10
+            ])
+        );
+        assert_eq!(json.as_object().unwrap().len(), 5);
+
+        for (module, event) in &[
+            (Some("erc20"), None),
+            (Some("ERC20"), Some("transfer")),
+            (None, Some("Transfer")),
+        ] {
+            events(*module, *event)
+                .to_json(&transfer(), &block)
+                .unwrap();
+        }
+        for (module, event) in &[(Some("voting"), None), (Some("erc20"), Some("Approval"))] {
+            assert!(events(*module, *event)
+                .to_json(&transfer(), &block)
+                .is_none());
+        }
+    }
+}
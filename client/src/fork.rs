@@ -0,0 +1,242 @@
+//! Snapshot the state of a running chain into the genesis of a new raw chainspec.
+//!
+//! The pinned substrate has no `state_getKeysPaged`, so all keys are listed with a single
+//! `state_getKeys` call, which the node answers in one response however large the state is.
+//! Values are then read with up to `CONCURRENT_READS` requests in flight. Every read is made at
+//! the same block.
+//!
+//! `--dev` only rewrites the current babe and grandpa authorities. This runtime has no session
+//! module and babe's epoch changes are externally triggered, so the authority set never rotates
+//! and Alice stays in control. A runtime that does rotate authorities would hand them back to the
+//! original validators at its first session change.
+
+use crate::json::Json;
+use crate::{BlockHash, ChainClient};
+use futures::compat::Future01CompatExt;
+use futures::future::{self, TryFutureExt};
+use futures::stream::{self, StreamExt, TryStreamExt};
+use jsonrpc_client_transports::RpcError;
+use node_template_runtime::Runtime;
+use parity_scale_codec::{DecodeAll, Encode};
+use serde::{Deserialize, Serialize};
+use sr_primitives::{AccountId32, BuildStorage, ChildrenStorageOverlay, StorageOverlay};
+use srml_support::storage::generator::{StorageMap, StorageValue};
+use std::collections::HashMap;
+use structopt::StructOpt;
+use substrate_chain_spec::ChainSpec;
+use substrate_finality_grandpa_primitives::AuthorityId as GrandpaId;
+use substrate_primitives_storage::{StorageData, StorageKey};
+use substrate_rpc_api::state::StateClient;
+use substrate_warmup_common::{parse_hash, sudo_key_raw_key, DevAccount};
+
+/// Free balance given to Alice by `--dev` when she has less, enough to pay transaction fees.
+const DEV_BALANCE: u128 = 1_000_000_000_000_000_000;
+
+const GRANDPA_AUTHORITIES_KEY: &[u8] = b":grandpa_authorities";
+
+/// Number of storage reads awaited at once while snapshotting.
+const CONCURRENT_READS: usize = 64;
+
+#[derive(StructOpt, Debug)]
+pub struct Fork {
+    /// Hash of the block to snapshot, defaults to the latest finalized block.
+    #[structopt(long, parse(try_from_str = parse_hash))]
+    at: Option<BlockHash>,
+    /// Human readable name of the forked chain.
+    #[structopt(long, default_value = "Substrate Warmup Fork")]
+    name: String,
+    /// Id of the forked chain. Also used as its network protocol id so forked nodes do not
+    /// connect to nodes of the original chain.
+    #[structopt(long, default_value = "substrate-warmup-fork")]
+    id: String,
+    /// Make Alice the sudo key and the only babe and grandpa authority, and fund her, so the
+    /// fork can be run locally with `--alice`. Only the current authorities are replaced, so a
+    /// runtime that rotates its authorities hands them back at its first session change.
+    #[structopt(long)]
+    dev: bool,
+}
+
+/// Genesis made of storage copied verbatim from another chain.
+#[derive(Clone, Serialize, Deserialize)]
+struct ForkedGenesis(HashMap<StorageKey, StorageData>);
+
+impl BuildStorage for ForkedGenesis {
+    fn assimilate_storage(
+        &self,
+        storage: &mut (StorageOverlay, ChildrenStorageOverlay),
+    ) -> Result<(), String> {
+        storage
+            .0
+            .extend(self.0.iter().map(|(k, v)| (k.0.clone(), v.0.clone())));
+        Ok(())
+    }
+}
+
+impl Fork {
+    /// Output the forked chain as a raw chainspec.
+    pub async fn run(
+        self,
+        state: StateClient<BlockHash>,
+        chain: ChainClient,
+    ) -> Result<Json, RpcError> {
+        let at = match self.at {
+            Some(at) => at,
+            None => chain.finalized_head().compat().await?,
+        };
+        let keys = state
+            .storage_keys(StorageKey(vec![]), Some(at))
+            .compat()
+            .await?;
+        let mut storage: HashMap<StorageKey, StorageData> = stream::iter(keys)
+            .map(|key| {
+                state
+                    .storage(key.clone(), Some(at))
+                    .compat()
+                    .map_ok(|value| value.map(|value| (key, value)))
+            })
+            .buffer_unordered(CONCURRENT_READS)
+            .try_filter_map(future::ok)
+            .try_collect()
+            .await?;
+        if self.dev {
+            make_dev(&mut storage).map_err(RpcError::Client)?;
+        }
+
+        let genesis = ForkedGenesis(storage);
+        let spec = ChainSpec::from_genesis(
+            &self.name,
+            &self.id,
+            move || genesis.clone(),
+            vec![],
+            None,
+            Some(&self.id),
+            None,
+            None,
+        )
+        .to_json(true)
+        .map_err(RpcError::Client)?;
+        Json::from_string(spec).map_err(|e| RpcError::Other(e.into()))
+    }
+}
+
+/// Hand control of the chain to Alice: she becomes sudo and the only block producer and
+/// finalizer, and is given enough funds to pay fees.
+fn make_dev(storage: &mut HashMap<StorageKey, StorageData>) -> Result<(), String> {
+    let alice = DevAccount::Alice.account_id();
+    let babe = DevAccount::Alice.babe_id();
+    let grandpa = DevAccount::Alice.grandpa_id();
+
+    insert(storage, sudo_key_raw_key(), &alice);
+    insert(
+        storage,
+        srml_babe::Authorities::storage_value_final_key().to_vec(),
+        &vec![(babe, 1u64)],
+    );
+
+    // keep the encoding used by the original chain, a version byte followed by the list, or the
+    // bare list
+    let grandpa_authorities = vec![(grandpa, 1u64)];
+    let versioned = read::<(u8, Vec<(GrandpaId, u64)>)>(storage, GRANDPA_AUTHORITIES_KEY);
+    let plain = read::<Vec<(GrandpaId, u64)>>(storage, GRANDPA_AUTHORITIES_KEY);
+    match (versioned, plain) {
+        (None, Some(_)) => insert(
+            storage,
+            GRANDPA_AUTHORITIES_KEY.to_vec(),
+            &grandpa_authorities,
+        ),
+        (Some((version, _)), _) => insert(
+            storage,
+            GRANDPA_AUTHORITIES_KEY.to_vec(),
+            &(version, grandpa_authorities),
+        ),
+        (None, None) => insert(
+            storage,
+            GRANDPA_AUTHORITIES_KEY.to_vec(),
+            &(1u8, grandpa_authorities),
+        ),
+    }
+
+    let free_key = srml_balances::FreeBalance::<Runtime>::storage_map_final_key(&alice)
+        .as_ref()
+        .to_vec();
+    let free = read::<u128>(storage, &free_key).unwrap_or(0);
+    if free < DEV_BALANCE {
+        let issuance_key = srml_balances::TotalIssuance::<Runtime>::storage_value_final_key();
+        let issuance = read::<u128>(storage, &issuance_key).unwrap_or(0);
+        let issuance = issuance
+            .checked_add(DEV_BALANCE - free)
+            .ok_or("funding Alice would overflow total issuance")?;
+        insert(storage, issuance_key.to_vec(), &issuance);
+        insert(storage, free_key, &DEV_BALANCE);
+    }
+    Ok(())
+}
+
+fn read<T: DecodeAll>(storage: &HashMap<StorageKey, StorageData>, key: &[u8]) -> Option<T> {
+    storage
+        .get(&StorageKey(key.to_vec()))
+        .and_then(|value| T::decode_all(&value.0).ok())
+}
+
+fn insert<T: Encode>(storage: &mut HashMap<StorageKey, StorageData>, key: Vec<u8>, value: &T) {
+    storage.insert(StorageKey(key), StorageData(value.encode()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use substrate_consensus_babe_primitives::AuthorityId as BabeId;
+
+    #[test]
+    fn t_make_dev() {
+        let bob = DevAccount::Bob.account_id();
+        let bob_grandpa = DevAccount::Bob.grandpa_id();
+        let mut storage = HashMap::new();
+        insert(&mut storage, sudo_key_raw_key(), &bob);
+        insert(
+            &mut storage,
+            GRANDPA_AUTHORITIES_KEY.to_vec(),
+            &(1u8, vec![(bob_grandpa, 1u64)]),
+        );
+        let issuance_key = srml_balances::TotalIssuance::<Runtime>::storage_value_final_key();
+        insert(&mut storage, issuance_key.to_vec(), &5u128);
+
+        make_dev(&mut storage).unwrap();
+
+        let alice = DevAccount::Alice.account_id();
+        assert_eq!(
+            read(&storage, &sudo_key_raw_key()),
+            Some(alice.clone())
+        );
+        assert_eq!(
+            read::<Vec<(BabeId, u64)>>(
+                &storage,
+                &srml_babe::Authorities::storage_value_final_key()
+            ),
+            Some(vec![(DevAccount::Alice.babe_id(), 1)])
+        );
+        assert_eq!(
+            read::<(u8, Vec<(GrandpaId, u64)>)>(&storage, GRANDPA_AUTHORITIES_KEY),
+            Some((1, vec![(DevAccount::Alice.grandpa_id(), 1)]))
+        );
+        let free_key = srml_balances::FreeBalance::<Runtime>::storage_map_final_key(&alice);
+        assert_eq!(read(&storage, free_key.as_ref()), Some(DEV_BALANCE));
+        assert_eq!(read(&storage, &issuance_key), Some(DEV_BALANCE + 5));
+    }
+
+    #[test]
+    fn t_make_dev_plain_grandpa() {
+        let bob_grandpa = DevAccount::Bob.grandpa_id();
+        let mut storage = HashMap::new();
+        insert(
+            &mut storage,
+            GRANDPA_AUTHORITIES_KEY.to_vec(),
+            &vec![(bob_grandpa, 1u64)],
+        );
+        make_dev(&mut storage).unwrap();
+        assert_eq!(
+            read::<Vec<(GrandpaId, u64)>>(&storage, GRANDPA_AUTHORITIES_KEY),
+            Some(vec![(DevAccount::Alice.grandpa_id(), 1)])
+        );
+    }
+}
@@ -4,6 +4,7 @@
 
 use serde::Serialize;
 
+#[derive(Clone)]
 pub struct Json(String);
 
 impl Json {
@@ -11,6 +12,21 @@ impl Json {
         serde_json::to_string(src).map(Self)
     }
 
+    /// Wrap a string that is already serialized json. Fails if `src` is not valid json.
+    pub fn from_string(src: String) -> Result<Self, serde_json::Error> {
+        serde_json::from_str::<serde::de::IgnoredAny>(&src)?;
+        Ok(Self(src))
+    }
+
+    /// Build a json object from values that are already serialized.
+    pub fn object<'a>(fields: impl IntoIterator<Item = (&'a str, Json)>) -> Self {
+        let fields: Vec<String> = fields
+            .into_iter()
+            .map(|(name, value)| format!("{}:{}", serde_json::to_string(name).unwrap(), value.0))
+            .collect();
+        Self(format!("{{{}}}", fields.join(",")))
+    }
+
     pub fn as_str(&self) -> &str {
         &self.0
     }
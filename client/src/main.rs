@@ -1,6 +1,10 @@
+mod events;
+mod fork;
 mod json;
 mod query;
 mod storage_query;
+mod submit;
+mod watch;
 
 use core::fmt::Debug;
 use futures::{
@@ -9,13 +13,23 @@ use futures::{
 };
 use json::Json;
 use jsonrpc_client_transports::transports::ws;
-use jsonrpc_client_transports::RpcError;
+use jsonrpc_client_transports::{RpcChannel, RpcError};
+use node_template_runtime::{Block, Runtime};
+use sr_primitives::generic::SignedBlock;
+use sr_primitives::traits::Block as BlockT;
 use structopt::StructOpt;
-use substrate_primitives_storage::StorageData;
+use substrate_rpc_api::author::AuthorClient;
 use substrate_rpc_api::state::StateClient;
 use url::Url;
 
-type BlockHash = <node_template_runtime::Runtime as srml_system::Trait>::Hash;
+type BlockHash = <Runtime as srml_system::Trait>::Hash;
+type BlockNumber = <Runtime as srml_system::Trait>::BlockNumber;
+type ChainClient = substrate_rpc_api::chain::ChainClient<
+    BlockNumber,
+    BlockHash,
+    <Block as BlockT>::Header,
+    SignedBlock<Block>,
+>;
 
 #[derive(StructOpt, Debug)]
 struct Args {
@@ -26,36 +40,64 @@ struct Args {
 
 #[derive(StructOpt, Debug)]
 enum Action {
-    Read(query::Key),
+    /// Output a storage value as json
+    Read(query::Read),
+    /// Output a raw chainspec whose genesis is the state of the connected chain. Keys are not
+    /// paged, the node lists all of them in one response.
+    Fork(fork::Fork),
+    /// Sign an extrinsic and submit it to the connected node, outputs the extrinsic's hash
+    Submit(submit::Submit),
+    /// Output a storage value as a line of json each time it changes
+    Watch(query::Key),
+    /// Output the events deposited by the runtime as lines of json, as blocks are imported
+    Events(events::Events),
 }
 
 #[paw::main]
 fn main(args: Args) {
-    let to_print: Json = tokio::runtime::Runtime::new()
+    let to_print: Option<Json> = tokio::runtime::Runtime::new()
         .unwrap()
         .block_on(Compat::new(amain(args).boxed()))
         .unwrap();
-    println!("{}", to_print.as_str());
+    if let Some(to_print) = to_print {
+        println!("{}", to_print.as_str());
+    }
 }
 
-async fn amain(args: Args) -> Result<Json, RpcError> {
-    let conn = ws::connect(&args.address.as_str().parse().unwrap())
+async fn amain(args: Args) -> Result<Option<Json>, RpcError> {
+    let conn: RpcChannel = ws::connect(&args.address.as_str().parse().unwrap())
         .compat()
         .await?;
-    let cl = StateClient::<BlockHash>::new(conn);
-    let opt: Option<Json> = do_action(cl, args.action).await?;
-    Ok(opt.unwrap_or_else(|| Json::create(&()).unwrap()))
+    do_action(conn, args.action).await
 }
 
-async fn do_action(cl: StateClient<BlockHash>, act: Action) -> Result<Option<Json>, RpcError> {
+async fn do_action(conn: RpcChannel, act: Action) -> Result<Option<Json>, RpcError> {
     match act {
-        Action::Read(key) => {
-            let raw_key = key.to_raw_key();
-            let raw_value_opt: Option<StorageData> = cl.storage(raw_key, None).compat().await?;
-            raw_value_opt
-                .map(|raw_value| key.raw_scale_to_json(raw_value))
-                .transpose()
-                .map_err(|e| RpcError::Other(e.into()))
+        Action::Read(read) => read
+            .run(StateClient::new(conn.clone()), ChainClient::new(conn))
+            .await
+            .map(Some),
+        Action::Fork(fork) => fork
+            .run(StateClient::new(conn.clone()), ChainClient::new(conn))
+            .await
+            .map(Some),
+        Action::Submit(submit) => submit
+            .run(
+                StateClient::new(conn.clone()),
+                ChainClient::new(conn.clone()),
+                AuthorClient::new(conn),
+            )
+            .await
+            .map(Some),
+        Action::Watch(key) => {
+            watch::watch(key, StateClient::new(conn.clone()), ChainClient::new(conn)).await?;
+            Ok(None)
+        }
+        Action::Events(events) => {
+            events
+                .run(StateClient::new(conn.clone()), ChainClient::new(conn))
+                .await?;
+            Ok(None)
         }
     }
 }
@@ -1,16 +1,37 @@
 use crate::json::Json;
 use crate::storage_query::{AugmentClap, MapQuery, StorageQuery, ValueQuery};
+use crate::{BlockHash, BlockNumber, ChainClient};
 use core::fmt::Debug;
 use core::ops::Deref;
-use node_template_runtime::Runtime;
+use futures::compat::Future01CompatExt;
+use jsonrpc_client_transports::RpcError;
+use node_template_runtime::{Block, Runtime};
+use sr_primitives::generic::BlockId;
 use sr_primitives::AccountId32;
 use structopt::StructOpt;
 use substrate_consensus_babe_primitives::BabeAuthorityWeight;
 use substrate_primitives::H256;
 use substrate_primitives_storage::{StorageData, StorageKey};
+use substrate_rpc_api::chain::number::NumberOrHex;
+use substrate_rpc_api::state::StateClient;
+use substrate_warmup_common::parse_hash;
 
 #[derive(StructOpt, Debug)]
-/// Key arguements should be provided as json.
+pub struct Read {
+    /// Block to read at, as a 0x prefixed hash or a block number. Defaults to the best block.
+    #[structopt(long, parse(try_from_str = parse_block_id))]
+    at: Option<BlockId<Block>>,
+    /// Output `{"block": {"hash", "number"}, "value"}`, naming the block the value was read at,
+    /// instead of the bare value.
+    #[structopt(long)]
+    with_block: bool,
+    #[structopt(subcommand)]
+    key: Key,
+}
+
+#[derive(StructOpt, Debug)]
+/// Key arguements should be provided as json. Accounts may be given as SS58, 0x prefixed hex, a
+/// secret uri such as "//Alice" or a dev account name such as "alice".
 pub enum Key {
     /// Numerical id of the next token to be minted. Any non-negative integer less than this value
     /// is a registered token.
@@ -20,7 +41,7 @@ pub enum Key {
     Erc20Token(MapQuery<erc20::Tokens<Runtime>, u32, erc20::Erc20Token<u128>>),
     /// Balance of token for account.
     /// args: `[<token-number>, "ss58address"]`
-    /// example, get Alice's balance for token 0: `[0, "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"]`
+    /// example, get Alice's balance for token 0: `[0, "alice"]`
     Erc20BalanceOf(MapQuery<erc20::BalanceOf<Runtime>, (u32, AccountId32), u128>),
     /// Tokens granted as an allowance.
     /// args: `[<token-number>, "benefactor_account", "recipient_account"]`
@@ -56,6 +77,65 @@ pub enum Key {
     VotingVoteRecordCount(ValueQuery<voting::VoteRecordCount, u64>),
 }
 
+impl Read {
+    /// Output the value, or null when it is not in storage. With `--with-block` the value is
+    /// output along with the block it was read at.
+    pub async fn run(
+        self,
+        state: StateClient<BlockHash>,
+        chain: ChainClient,
+    ) -> Result<Json, RpcError> {
+        let hash = match self.at {
+            Some(BlockId::Hash(hash)) => Some(hash),
+            Some(BlockId::Number(number)) => {
+                let number = NumberOrHex::Number(number);
+                chain.block_hash(Some(number)).compat().await?
+            }
+            None => chain.block_hash(None).compat().await?,
+        };
+        let hash = hash.ok_or_else(|| RpcError::Client("block not found".to_string()))?;
+
+        let value = state
+            .storage(self.key.to_raw_key(), Some(hash))
+            .compat()
+            .await?
+            .map(|raw_value| self.key.raw_scale_to_json(raw_value))
+            .transpose()
+            .map_err(|e| RpcError::Other(e.into()))?;
+        if self.with_block {
+            at_block(&chain, hash, value).await
+        } else {
+            Ok(value.unwrap_or_else(|| Json::create(&()).unwrap()))
+        }
+    }
+}
+
+/// Output a value, or null, along with the hash and number of the block it was read at.
+/// Panics if in-memory serialization fails.
+pub async fn at_block(
+    chain: &ChainClient,
+    hash: BlockHash,
+    value: Option<Json>,
+) -> Result<Json, RpcError> {
+    let block = block(chain, hash).await?;
+    let value = value.unwrap_or_else(|| Json::create(&()).unwrap());
+    Ok(Json::object(vec![("block", block), ("value", value)]))
+}
+
+/// The hash and number of a block, as a json object.
+/// Panics if in-memory serialization fails.
+pub async fn block(chain: &ChainClient, hash: BlockHash) -> Result<Json, RpcError> {
+    let header = chain
+        .header(Some(hash))
+        .compat()
+        .await?
+        .ok_or_else(|| RpcError::Client(format!("block {:?} not found", hash)))?;
+    Ok(Json::object(vec![
+        ("hash", Json::create(&hash).unwrap()),
+        ("number", Json::create(&header.number).unwrap()),
+    ]))
+}
+
 impl StorageQuery for Key {
     fn to_raw_key(&self) -> StorageKey {
         self.deref().to_raw_key()
@@ -91,3 +171,33 @@ impl Deref for Key {
         }
     }
 }
+
+fn parse_block_id(imp: &str) -> Result<BlockId<Block>, String> {
+    if imp.starts_with("0x") {
+        parse_hash(imp)
+            .map(BlockId::Hash)
+            .map_err(|e| format!("invalid block {:?}: {}", imp, e))
+    } else {
+        imp.parse::<BlockNumber>()
+            .map(BlockId::Number)
+            .map_err(|e| format!("invalid block {:?}: {}", imp, e))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn t_parse_block_id() {
+        let hash = "0x0000000000000000000000000000000000000000000000000000000000000001";
+        assert_eq!(
+            parse_block_id(hash),
+            Ok(BlockId::Hash(BlockHash::from_low_u64_be(1)))
+        );
+        assert_eq!(parse_block_id("12"), Ok(BlockId::Number(12)));
+        parse_block_id("0x01").unwrap_err();
+        parse_block_id("-1").unwrap_err();
+        parse_block_id("latest").unwrap_err();
+    }
+}
@@ -1 +1,125 @@
+use super::augment_clap::AugmentClap;
+use super::Json;
+use crate::storage_query::{parse_json_key, StorageQuery};
+use core::fmt::Debug;
+use core::marker::PhantomData;
+use parity_scale_codec::FullCodec;
+use serde::{de::DeserializeOwned, Serialize};
+use srml_support::storage::generator::StorageDoubleMap;
+use structopt::clap::{self, App, ArgMatches};
+use structopt::StructOpt;
+use substrate_primitives_storage::{StorageData, StorageKey};
 
+/// Query for a double map entry, taking its two keys as separate json arguments.
+// no storage item of the runtime is a double map yet
+#[allow(dead_code)]
+pub struct DoubleMapQuery<S, K1, K2, V> {
+    key1: K1,
+    key2: K2,
+    _spook: PhantomData<(S, V)>,
+}
+
+impl<S, K1: Debug, K2: Debug, V> Debug for DoubleMapQuery<S, K1, K2, V> {
+    fn fmt(&self, fmt: &mut core::fmt::Formatter<'_>) -> Result<(), core::fmt::Error> {
+        Debug::fmt(&(&self.key1, &self.key2), fmt)
+    }
+}
+
+impl<S: StorageDoubleMap<K1, K2, V>, K1: FullCodec, K2: FullCodec, V: FullCodec + Serialize>
+    StorageQuery for DoubleMapQuery<S, K1, K2, V>
+{
+    fn to_raw_key(&self) -> StorageKey {
+        StorageKey(S::storage_double_map_final_key(&self.key1, &self.key2))
+    }
+
+    fn raw_scale_to_json(&self, raw: StorageData) -> Result<Json, parity_scale_codec::Error> {
+        super::raw_scale_to_json::<V>(raw)
+    }
+}
+
+impl<S, K1: DeserializeOwned, K2: DeserializeOwned, V> StructOpt for DoubleMapQuery<S, K1, K2, V> {
+    fn clap<'a, 'b>() -> App<'a, 'b> {
+        Self::augment_clap(App::new(""))
+    }
+
+    fn from_clap(matches: &ArgMatches<'_>) -> Self {
+        Self {
+            key1: matches
+                .value_of("key1")
+                .map(|s| parse_json_key(s).unwrap())
+                .unwrap(),
+            key2: matches
+                .value_of("key2")
+                .map(|s| parse_json_key(s).unwrap())
+                .unwrap(),
+            _spook: PhantomData,
+        }
+    }
+}
+
+impl<S, K1: DeserializeOwned, K2: DeserializeOwned, V> AugmentClap
+    for DoubleMapQuery<S, K1, K2, V>
+{
+    fn augment_clap<'a, 'b>(app: App<'a, 'b>) -> App<'a, 'b> {
+        app.arg(
+            clap::Arg::with_name("key1")
+                .takes_value(true)
+                .multiple(false)
+                .required(true)
+                .validator(|s| parse_json_key(&s).map(|_: K1| ())),
+        )
+        .arg(
+            clap::Arg::with_name("key2")
+                .takes_value(true)
+                .multiple(false)
+                .required(true)
+                .validator(|s| parse_json_key(&s).map(|_: K2| ())),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use node_template_runtime::Runtime;
+    use sr_primitives::AccountId32;
+    use srml_support::storage::StorageDoubleMap as _;
+    use substrate_warmup_common::DevAccount;
+
+    mod mock {
+        use srml_support::{decl_module, decl_storage};
+        use srml_system as system;
+
+        pub trait Trait: system::Trait {}
+
+        decl_module! {
+            pub struct Module<T: Trait> for enum Call where origin: T::Origin {}
+        }
+
+        decl_storage! {
+            trait Store for Module<T: Trait> as Mock {
+                pub Allowance: double_map (u32, T::AccountId), blake2_256(T::AccountId) => u128;
+            }
+        }
+    }
+
+    impl mock::Trait for Runtime {}
+
+    #[test]
+    fn t_double_map_query() {
+        let query = DoubleMapQuery::<
+            mock::Allowance<Runtime>,
+            (u32, AccountId32),
+            AccountId32,
+            u128,
+        >::from_iter(&["", r#"[0, "alice"]"#, r#""bob""#]);
+        let owner = (0, DevAccount::Alice.account_id());
+        let spender = DevAccount::Bob.account_id();
+        assert_eq!(query.key1, owner);
+        assert_eq!(query.key2, spender);
+        assert_eq!(
+            query.to_raw_key(),
+            StorageKey(mock::Allowance::<Runtime>::hashed_key_for(&owner, &spender))
+        );
+    }
+}
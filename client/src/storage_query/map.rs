@@ -1,6 +1,6 @@
 use super::augment_clap::AugmentClap;
 use super::Json;
-use crate::storage_query::StorageQuery;
+use crate::storage_query::{parse_json_key, StorageQuery};
 use core::fmt::Debug;
 use core::marker::PhantomData;
 use parity_scale_codec::FullCodec;
@@ -42,7 +42,7 @@ impl<S, K: DeserializeOwned, V> StructOpt for MapQuery<S, K, V> {
         Self {
             key: matches
                 .value_of("key")
-                .map(|s| serde_json::from_str(s).unwrap())
+                .map(|s| parse_json_key(s).unwrap())
                 .unwrap(),
             _spook: PhantomData,
         }
@@ -56,11 +56,7 @@ impl<S, K: DeserializeOwned, V> AugmentClap for MapQuery<S, K, V> {
                 .takes_value(true)
                 .multiple(false)
                 .required(true)
-                .validator(|s| {
-                    serde_json::from_str(s.as_str())
-                        .map(|_: K| ())
-                        .map_err(|e| e.to_string())
-                }),
+                .validator(|s| parse_json_key(&s).map(|_: K| ())),
         )
     }
 }
@@ -6,10 +6,13 @@ mod value;
 
 use super::json::Json;
 use parity_scale_codec::DecodeAll;
-use serde::Serialize;
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::Value;
 use substrate_primitives_storage::{StorageData, StorageKey};
+use substrate_warmup_common::parse_accountid32;
 
 pub use augment_clap::AugmentClap;
+pub use double_map::DoubleMapQuery;
 pub use map::MapQuery;
 pub use value::ValueQuery;
 
@@ -27,3 +30,64 @@ fn raw_scale_to_json<T: DecodeAll + Serialize>(
     let ret = T::decode_all(&raw.0)?;
     Ok(Json::create(&ret).unwrap())
 }
+
+/// Parse a storage key from json. Accounts deserialize from SS58 only, so when `imp` does not
+/// parse as is, any string accepted by `parse_accountid32` (hex, SS58, a secret uri such as
+/// `//Alice` or a dev account name such as `alice`) is rewritten as SS58 and parsing is
+/// retried. If that fails too and some string was not a valid account, the reason it was
+/// rejected is reported.
+fn parse_json_key<K: DeserializeOwned>(imp: &str) -> Result<K, String> {
+    let err = match serde_json::from_str(imp) {
+        Ok(key) => return Ok(key),
+        Err(e) => e.to_string(),
+    };
+    let mut value: Value = serde_json::from_str(imp).map_err(|_| err.clone())?;
+    let mut invalid = None;
+    accounts_to_ss58(&mut value, &mut invalid);
+    serde_json::from_value(value).map_err(|_| invalid.unwrap_or(err))
+}
+
+fn accounts_to_ss58(value: &mut Value, invalid: &mut Option<String>) {
+    match value {
+        Value::String(s) => match parse_accountid32(s) {
+            Ok(account) => *s = account.to_string(),
+            Err(e) => {
+                invalid.get_or_insert_with(|| format!("invalid account {:?}: {}", s, e));
+            }
+        },
+        Value::Array(values) => values
+            .iter_mut()
+            .for_each(|value| accounts_to_ss58(value, invalid)),
+        Value::Object(fields) => fields
+            .values_mut()
+            .for_each(|value| accounts_to_ss58(value, invalid)),
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sr_primitives::AccountId32;
+
+    #[test]
+    fn t_parse_json_key() {
+        let alice = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+        let expected: (u32, AccountId32) =
+            serde_json::from_str(&format!("[0, {:?}]", alice)).unwrap();
+        for imp in &[
+            format!("[0, {:?}]", alice),
+            r#"[0, "//Alice"]"#.to_string(),
+            r#"[0, "alice"]"#.to_string(),
+            r#"[0, "0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d"]"#
+                .to_string(),
+        ] {
+            assert_eq!(parse_json_key::<(u32, AccountId32)>(imp).unwrap(), expected);
+        }
+        assert_eq!(
+            parse_json_key::<(u32, AccountId32)>(r#"[0, "Alicia"]"#).unwrap_err(),
+            r#"invalid account "Alicia": invalid ss58 character 'l' at position 1"#
+        );
+        parse_json_key::<(u32, AccountId32)>("not json").unwrap_err();
+    }
+}
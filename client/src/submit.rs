@@ -0,0 +1,285 @@
+//! Sign an extrinsic and submit it to the connected node.
+//!
+//! Extrinsics are immortal and signed over the runtime version and genesis hash reported by the
+//! node. Unless `--nonce` is given, the nonce is read from the best block, so extrinsics from the
+//! same signer that are still waiting in the transaction pool are not accounted for.
+
+use crate::json::Json;
+use crate::{BlockHash, ChainClient};
+use core::fmt;
+use futures::compat::Future01CompatExt;
+use jsonrpc_client_transports::RpcError;
+use node_template_runtime::{Call, Runtime, SignedExtra, UncheckedExtrinsic};
+use parity_scale_codec::{Decode, Encode};
+use sr_primitives::generic::Era;
+use sr_primitives::{AccountId32, MultiSignature};
+use srml_support::storage::generator::StorageMap;
+use structopt::StructOpt;
+use substrate_primitives::{blake2_256, sr25519, Bytes, Pair};
+use substrate_primitives_storage::StorageKey;
+use substrate_rpc_api::author::AuthorClient;
+use substrate_rpc_api::chain::number::NumberOrHex;
+use substrate_rpc_api::state::StateClient;
+use substrate_warmup_common::{parse_accountid32, DevAccount};
+
+type Index = <Runtime as srml_system::Trait>::Index;
+
+#[derive(StructOpt, Debug)]
+pub struct Submit {
+    /// Secret uri of the sr25519 key signing the extrinsic, such as "//Alice", or a dev account
+    /// name such as "alice".
+    #[structopt(long, parse(try_from_str = parse_signer))]
+    suri: Signer,
+    /// Nonce of the extrinsic, defaults to the signer's nonce at the best block.
+    #[structopt(long)]
+    nonce: Option<Index>,
+    /// Tip paid to the block author on top of the transaction fee.
+    #[structopt(long, default_value = "0")]
+    tip: u128,
+    #[structopt(subcommand)]
+    extrinsic: Extrinsic,
+}
+
+/// Accounts may be given as SS58, 0x prefixed hex, a secret uri such as "//Alice" or a dev
+/// account name such as "alice".
+#[derive(StructOpt, Debug)]
+pub enum Extrinsic {
+    /// Send tokens from the signer's account.
+    Erc20Transfer {
+        token_id: u32,
+        #[structopt(parse(try_from_str = parse_accountid32))]
+        to: AccountId32,
+        value: u128,
+    },
+    /// Allow `spender` to transfer up to `value` of the signer's tokens.
+    Erc20Approve {
+        token_id: u32,
+        #[structopt(parse(try_from_str = parse_accountid32))]
+        spender: AccountId32,
+        value: u128,
+    },
+    /// Send tokens the owner of `from` has allowed the signer to spend.
+    Erc20TransferFrom {
+        token_id: u32,
+        #[structopt(parse(try_from_str = parse_accountid32))]
+        from: AccountId32,
+        #[structopt(parse(try_from_str = parse_accountid32))]
+        to: AccountId32,
+        value: u128,
+    },
+    /// Destroy tokens held by the signer.
+    Erc20Burn { token_id: u32, value: u128 },
+    /// Mint a new token whose whole supply is held by `beneficiary`. The call is wrapped in
+    /// `sudo`, so the signer must be the sudo key.
+    Erc20Init {
+        #[structopt(parse(try_from_str = parse_accountid32))]
+        beneficiary: AccountId32,
+        name: String,
+        ticker: String,
+        total_supply: u128,
+    },
+}
+
+/// Key pair signing extrinsics. Only the public key is shown when debug printed.
+pub struct Signer(sr25519::Pair);
+
+impl Submit {
+    /// Submit the extrinsic and output its hash.
+    pub async fn run(
+        self,
+        state: StateClient<BlockHash>,
+        chain: ChainClient,
+        author: AuthorClient<BlockHash, BlockHash>,
+    ) -> Result<Json, RpcError> {
+        let signer = self.suri.0;
+        let nonce = match self.nonce {
+            Some(nonce) => nonce,
+            None => {
+                let account: AccountId32 = signer.public().0.into();
+                let key = srml_system::AccountNonce::<Runtime>::storage_map_final_key(&account);
+                state
+                    .storage(StorageKey(key.as_ref().to_vec()), None)
+                    .compat()
+                    .await?
+                    .map(|nonce| Index::decode(&mut &nonce.0[..]))
+                    .transpose()
+                    .map_err(|e| RpcError::Other(e.into()))?
+                    .unwrap_or(0)
+            }
+        };
+        let genesis = chain
+            .block_hash(Some(NumberOrHex::Number(0)))
+            .compat()
+            .await?
+            .ok_or_else(|| RpcError::Client("node did not return a genesis hash".to_string()))?;
+        let version = state.runtime_version(None).compat().await?;
+
+        let extrinsic = sign(
+            &signer,
+            self.extrinsic.into_call(),
+            nonce,
+            self.tip,
+            version.spec_version,
+            genesis,
+        );
+        let hash = author
+            .submit_extrinsic(Bytes(extrinsic.encode()))
+            .compat()
+            .await?;
+        Json::create(&hash).map_err(|e| RpcError::Other(e.into()))
+    }
+}
+
+impl Extrinsic {
+    fn into_call(self) -> Call {
+        match self {
+            Extrinsic::Erc20Transfer {
+                token_id,
+                to,
+                value,
+            } => Call::Erc20(erc20::Call::transfer(token_id, to, value)),
+            Extrinsic::Erc20Approve {
+                token_id,
+                spender,
+                value,
+            } => Call::Erc20(erc20::Call::approve(token_id, spender, value)),
+            Extrinsic::Erc20TransferFrom {
+                token_id,
+                from,
+                to,
+                value,
+            } => Call::Erc20(erc20::Call::transfer_from(token_id, from, to, value)),
+            Extrinsic::Erc20Burn { token_id, value } => {
+                Call::Erc20(erc20::Call::burn(token_id, value))
+            }
+            Extrinsic::Erc20Init {
+                beneficiary,
+                name,
+                ticker,
+                total_supply,
+            } => {
+                let init = erc20::Call::init(
+                    beneficiary,
+                    name.into_bytes(),
+                    ticker.into_bytes(),
+                    total_supply,
+                );
+                Call::Sudo(srml_sudo::Call::sudo(Box::new(Call::Erc20(init))))
+            }
+        }
+    }
+}
+
+/// Build an immortal extrinsic signed with the runtime's `SignedExtra`.
+fn sign(
+    signer: &sr25519::Pair,
+    call: Call,
+    nonce: Index,
+    tip: u128,
+    spec_version: u32,
+    genesis: BlockHash,
+) -> UncheckedExtrinsic {
+    let extra: SignedExtra = (
+        srml_system::CheckVersion::new(),
+        srml_system::CheckGenesis::new(),
+        srml_system::CheckEra::from(Era::Immortal),
+        srml_system::CheckNonce::from(nonce),
+        srml_system::CheckWeight::new(),
+        srml_transaction_payment::ChargeTransactionPayment::from(tip),
+    );
+    // the `additional_signed` of each extension, as the runtime will compute it. Immortal
+    // extrinsics are checked against the genesis hash by both CheckGenesis and CheckEra.
+    let additional = (spec_version, genesis, genesis, (), (), ());
+    // payloads longer than 256 bytes are hashed before being signed
+    let signature = (&call, &extra, &additional).using_encoded(|payload| {
+        if payload.len() > 256 {
+            signer.sign(&blake2_256(payload))
+        } else {
+            signer.sign(payload)
+        }
+    });
+    UncheckedExtrinsic::new_signed(
+        call,
+        signer.public().0.into(),
+        MultiSignature::from(signature),
+        extra,
+    )
+}
+
+fn parse_signer(imp: &str) -> Result<Signer, String> {
+    let suri = DevAccount::from_name(imp)
+        .map(DevAccount::secret_uri)
+        .unwrap_or_else(|| imp.to_string());
+    sr25519::Pair::from_string(&suri, None)
+        .map(Signer)
+        .map_err(|e| format!("invalid secret uri {:?}: {:?}", imp, e))
+}
+
+impl fmt::Debug for Signer {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Signer({})", AccountId32::from(self.0.public().0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sr_primitives::traits::Verify;
+
+    #[test]
+    fn t_parse_signer() {
+        let alice = DevAccount::Alice.account_id();
+        for imp in &["alice", "Alice", "//Alice"] {
+            let signer = parse_signer(imp).unwrap();
+            assert_eq!(AccountId32::from(signer.0.public().0), alice);
+        }
+        parse_signer("Alicia").unwrap_err();
+    }
+
+    #[test]
+    fn t_sign() {
+        let alice = DevAccount::Alice.sr25519();
+        let genesis = BlockHash::repeat_byte(1);
+        let transfer = Extrinsic::Erc20Transfer {
+            token_id: 0,
+            to: DevAccount::Bob.account_id(),
+            value: 10,
+        };
+        let init = Extrinsic::Erc20Init {
+            beneficiary: DevAccount::Bob.account_id(),
+            name: "x".repeat(300),
+            ticker: "X".to_string(),
+            total_supply: 10,
+        };
+
+        for extrinsic in vec![transfer, init] {
+            let call = extrinsic.into_call();
+            let xt = sign(&alice, call.clone(), 3, 0, 1, genesis);
+            let (account, signature, extra) = xt.signature.unwrap();
+            assert_eq!(account, DevAccount::Alice.account_id());
+            let mut payload = (&call, &extra, &(1u32, genesis, genesis, (), (), ())).encode();
+            if payload.len() > 256 {
+                payload = blake2_256(&payload).to_vec();
+            }
+            assert!(signature.verify(&payload[..], &account));
+        }
+    }
+
+    #[test]
+    fn t_init_is_sudo() {
+        let call = Extrinsic::Erc20Init {
+            beneficiary: DevAccount::Bob.account_id(),
+            name: "PSTABLE1".to_string(),
+            ticker: "PSTABLE1".to_string(),
+            total_supply: 10,
+        }
+        .into_call();
+        match call {
+            Call::Sudo(srml_sudo::Call::sudo(proposal)) => match *proposal {
+                Call::Erc20(erc20::Call::init(..)) => {}
+                other => panic!("unexpected proposal {:?}", other),
+            },
+            other => panic!("unexpected call {:?}", other),
+        }
+    }
+}
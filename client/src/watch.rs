@@ -0,0 +1,39 @@
+//! Print a storage value as a line of json each time it changes.
+//!
+//! The node sends the current value as soon as the subscription is made, so the first line is the
+//! value at the best block. Lines have the same shape as the output of `read`.
+
+use crate::query::{at_block, Key};
+use crate::storage_query::StorageQuery;
+use crate::{BlockHash, ChainClient};
+use futures::compat::Stream01CompatExt;
+use futures::stream::TryStreamExt;
+use jsonrpc_client_transports::RpcError;
+use substrate_rpc_api::state::StateClient;
+
+/// Runs until the node closes the subscription.
+pub async fn watch(
+    key: Key,
+    state: StateClient<BlockHash>,
+    chain: ChainClient,
+) -> Result<(), RpcError> {
+    let raw_key = key.to_raw_key();
+    let mut change_sets = state
+        .subscribe_storage(Some(vec![raw_key.clone()]))?
+        .compat();
+    while let Some(change_set) = change_sets.try_next().await? {
+        let changes = change_set
+            .changes
+            .into_iter()
+            .filter(|(changed, _)| *changed == raw_key);
+        for (_, raw_value) in changes {
+            let value = raw_value
+                .map(|raw_value| key.raw_scale_to_json(raw_value))
+                .transpose()
+                .map_err(|e| RpcError::Other(e.into()))?;
+            let line = at_block(&chain, change_set.block, value).await?;
+            println!("{}", line.as_str());
+        }
+    }
+    Ok(())
+}
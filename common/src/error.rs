@@ -0,0 +1,92 @@
+//! Errors returned when a key can not be parsed. Positions are byte offsets into the string that
+//! was parsed, including any `0x` prefix.
+
+use core::fmt;
+use substrate_primitives::crypto::SecretStringError;
+
+/// Encoding a key was being decoded from when an error occurred.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Encoding {
+    /// `0x` prefixed hex, 64 digits long.
+    Hex,
+    /// Base58 SS58 address, 35 bytes once decoded.
+    Ss58,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Error {
+    /// A hex key did not start with `0x`.
+    MissingPrefix,
+    /// The key has the wrong number of hex digits, or the SS58 address decodes to the wrong
+    /// number of bytes.
+    WrongLength {
+        encoding: Encoding,
+        expected: usize,
+        found: usize,
+    },
+    /// A character that does not belong to the key's encoding.
+    InvalidCharacter {
+        encoding: Encoding,
+        character: char,
+        position: usize,
+    },
+    /// The SS58 checksum does not match the encoded key.
+    BadChecksum,
+    /// The SS58 address is in the format of a different network.
+    UnsupportedFormat { expected: u8, found: u8 },
+    /// The secret uri could not be turned into a key pair.
+    SecretUri(SecretStringError),
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Encoding::Hex => write!(f, "hex"),
+            Encoding::Ss58 => write!(f, "ss58"),
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::MissingPrefix => write!(f, "hex key should be prefixed with '0x'"),
+            Error::WrongLength {
+                encoding: Encoding::Hex,
+                expected,
+                found,
+            } => write!(
+                f,
+                "hex key should be {} digits long, not {}",
+                expected, found
+            ),
+            Error::WrongLength {
+                encoding: Encoding::Ss58,
+                expected,
+                found,
+            } => write!(
+                f,
+                "ss58 address should decode to {} bytes, not {}",
+                expected, found
+            ),
+            Error::InvalidCharacter {
+                encoding,
+                character,
+                position,
+            } => write!(
+                f,
+                "invalid {} character {:?} at position {}",
+                encoding, character, position
+            ),
+            Error::BadChecksum => write!(f, "ss58 address has an invalid checksum"),
+            Error::UnsupportedFormat { expected, found } => write!(
+                f,
+                "ss58 address format {} is not supported, expected {}",
+                found, expected
+            ),
+            Error::SecretUri(e) => write!(f, "invalid secret uri: {:?}", e),
+        }
+    }
+}
+
+impl std::error::Error for Error {}
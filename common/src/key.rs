@@ -0,0 +1,175 @@
+//! Parsing of 32 byte public keys from any of the formats our tools accept:
+//!
+//! - `0x` prefixed hex, e.g. `0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d`
+//! - SS58, e.g. `5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY`
+//! - secret uris, e.g. `//Alice` or `<mnemonic>//stash`. Only the public part of the derived key
+//!   is kept.
+//! - names of dev accounts, e.g. `alice`, which are shorthand for their secret uri `//Alice`.
+
+use crate::{parse_key_256, slice_to_arr32, DevAccount, Encoding, Error};
+use substrate_primitives::hashing::blake2_512;
+use substrate_primitives::{Pair, Public};
+
+/// Address prefix used by generic substrate chains, including ours.
+pub const SS58_PREFIX: u8 = 42;
+
+/// Length of a decoded SS58 address: one prefix byte, the key and a two byte checksum.
+const SS58_LEN: usize = 1 + 32 + 2;
+
+/// Characters of the bitcoin base58 alphabet, which SS58 uses.
+const BASE58_ALPHABET: &str = "123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+/// Parse a public key from hex, SS58, a secret uri or a dev account name. Secret uris are derived
+/// using the key type's own crypto, so `//Alice` gives Alice's sr25519 key for babe and her
+/// ed25519 key for grandpa.
+pub fn parse_key<T: Public>(imp: &str) -> Result<T, Error> {
+    if let Some(account) = DevAccount::from_name(imp) {
+        Ok(account.public())
+    } else if imp.starts_with("0x") {
+        parse_key_256(imp).map(|key| T::from_slice(&key))
+    } else if is_secret_uri(imp) {
+        let pair = T::Pair::from_string(imp, None).map_err(Error::SecretUri)?;
+        Ok(T::from_slice(pair.public().as_ref()))
+    } else {
+        parse_ss58(imp, SS58_PREFIX).map(|key| T::from_slice(&key))
+    }
+}
+
+/// Secret uris contain a derivation path or a space separated mnemonic, neither of which can
+/// appear in hex or base58.
+fn is_secret_uri(imp: &str) -> bool {
+    imp.contains('/') || imp.contains(char::is_whitespace)
+}
+
+/// Decode an SS58 address, checking its checksum and network prefix.
+pub fn parse_ss58(imp: &str, prefix: u8) -> Result<[u8; 32], Error> {
+    if let Some((position, character)) = imp
+        .char_indices()
+        .find(|(_, c)| !BASE58_ALPHABET.contains(*c))
+    {
+        return Err(Error::InvalidCharacter {
+            encoding: Encoding::Ss58,
+            character,
+            position,
+        });
+    }
+    // every character is in the alphabet, so decoding can not fail
+    let raw = bs58::decode(imp).into_vec().unwrap_or_default();
+    if raw.len() != SS58_LEN {
+        return Err(Error::WrongLength {
+            encoding: Encoding::Ss58,
+            expected: SS58_LEN,
+            found: raw.len(),
+        });
+    }
+    let (body, checksum) = raw.split_at(1 + 32);
+    if ss58_checksum(body)[..2] != *checksum {
+        return Err(Error::BadChecksum);
+    }
+    if body[0] != prefix {
+        return Err(Error::UnsupportedFormat {
+            expected: prefix,
+            found: body[0],
+        });
+    }
+    Ok(slice_to_arr32(&body[1..]))
+}
+
+fn ss58_checksum(body: &[u8]) -> [u8; 64] {
+    let mut preimage = b"SS58PRE".to_vec();
+    preimage.extend_from_slice(body);
+    blake2_512(&preimage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use substrate_primitives::crypto::{SecretStringError, Ss58Codec};
+    use substrate_primitives::{ed25519, sr25519};
+
+    const ALICE_HEX: &str = "0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d";
+    const ALICE_SS58: &str = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+
+    fn alice() -> sr25519::Public {
+        sr25519::Pair::from_string("//Alice", None)
+            .unwrap()
+            .public()
+    }
+
+    #[test]
+    fn t_parse_key_formats() {
+        for imp in &[ALICE_HEX, ALICE_SS58, "//Alice", "alice", "Alice"] {
+            assert_eq!(parse_key::<sr25519::Public>(imp).unwrap(), alice());
+        }
+        assert_eq!(alice().to_ss58check(), ALICE_SS58);
+    }
+
+    #[test]
+    fn t_parse_key_uses_key_crypto() {
+        let ed = parse_key::<ed25519::Public>("//Alice").unwrap();
+        assert_eq!(
+            ed,
+            ed25519::Pair::from_string("//Alice", None)
+                .unwrap()
+                .public()
+        );
+        assert_ne!(ed.as_ref(), alice().as_ref());
+    }
+
+    #[test]
+    fn t_parse_key_errors() {
+        let parse = parse_key::<sr25519::Public>;
+        assert_eq!(
+            parse("0x1234"),
+            Err(Error::WrongLength {
+                encoding: Encoding::Hex,
+                expected: 64,
+                found: 4
+            })
+        );
+        assert_eq!(
+            parse("Alicia"),
+            Err(Error::InvalidCharacter {
+                encoding: Encoding::Ss58,
+                character: 'l',
+                position: 1
+            })
+        );
+        assert_eq!(
+            parse("Davy"),
+            Err(Error::WrongLength {
+                encoding: Encoding::Ss58,
+                expected: SS58_LEN,
+                found: 3
+            })
+        );
+        assert_eq!(
+            parse("not a mnemonic"),
+            Err(Error::SecretUri(SecretStringError::InvalidPhrase))
+        );
+
+        // flip a character in the checksum
+        let mut bad_checksum = ALICE_SS58.to_string();
+        bad_checksum.pop();
+        bad_checksum.push('Z');
+        assert_eq!(parse(&bad_checksum), Err(Error::BadChecksum));
+    }
+
+    #[test]
+    fn t_parse_ss58_prefix() {
+        let mut body = vec![2u8];
+        body.extend_from_slice(alice().as_ref());
+        let checksum = ss58_checksum(&body);
+        body.extend_from_slice(&checksum[..2]);
+        let kusama = bs58::encode(body).into_string();
+
+        assert_eq!(parse_ss58(&kusama, 2).unwrap(), alice().0);
+        assert_eq!(
+            parse_ss58(&kusama, SS58_PREFIX),
+            Err(Error::UnsupportedFormat {
+                expected: SS58_PREFIX,
+                found: 2
+            })
+        );
+    }
+}
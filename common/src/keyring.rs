@@ -0,0 +1,142 @@
+//! The well known dev accounts. Their keys are derived from the secret uri `//<Name>`, so they
+//! match the `--alice`, `--bob`, ... flags of substrate nodes and the accounts polkadot.js
+//! shows on dev chains.
+
+use core::fmt;
+use core::str::FromStr;
+use sr_primitives::AccountId32;
+use substrate_consensus_babe_primitives::AuthorityId as BabeId;
+use substrate_finality_grandpa_primitives::AuthorityId as GrandpaId;
+use substrate_primitives::{ed25519, sr25519, Pair, Public};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DevAccount {
+    Alice,
+    Bob,
+    Charlie,
+    Dave,
+    Eve,
+    Ferdie,
+}
+
+impl DevAccount {
+    /// Every dev account, in the order nodes and wallets list them.
+    pub const ALL: [DevAccount; 6] = [
+        DevAccount::Alice,
+        DevAccount::Bob,
+        DevAccount::Charlie,
+        DevAccount::Dave,
+        DevAccount::Eve,
+        DevAccount::Ferdie,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            DevAccount::Alice => "Alice",
+            DevAccount::Bob => "Bob",
+            DevAccount::Charlie => "Charlie",
+            DevAccount::Dave => "Dave",
+            DevAccount::Eve => "Eve",
+            DevAccount::Ferdie => "Ferdie",
+        }
+    }
+
+    /// Look up a dev account by name, ignoring case, so both `alice` and `Alice` are accepted.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .cloned()
+            .find(|account| account.name().eq_ignore_ascii_case(name))
+    }
+
+    /// Secret uri the account's keys are derived from.
+    pub fn secret_uri(self) -> String {
+        format!("//{}", self.name())
+    }
+
+    /// Derive the account's key pair for any crypto.
+    pub fn pair<P: Pair>(self) -> P {
+        P::from_string(&self.secret_uri(), None).expect("dev secret uris are valid")
+    }
+
+    /// Derive the account's public key using the key type's own crypto.
+    pub fn public<T: Public>(self) -> T {
+        T::from_slice(self.pair::<T::Pair>().public().as_ref())
+    }
+
+    pub fn sr25519(self) -> sr25519::Pair {
+        self.pair()
+    }
+
+    pub fn ed25519(self) -> ed25519::Pair {
+        self.pair()
+    }
+
+    /// Account id of the sr25519 key, the one dev chains endow.
+    pub fn account_id(self) -> AccountId32 {
+        self.public::<sr25519::Public>().0.into()
+    }
+
+    pub fn babe_id(self) -> BabeId {
+        self.public()
+    }
+
+    pub fn grandpa_id(self) -> GrandpaId {
+        self.public()
+    }
+}
+
+impl fmt::Display for DevAccount {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for DevAccount {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, String> {
+        Self::from_name(s).ok_or_else(|| format!("{:?} is not a dev account", s))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn t_dev_accounts() {
+        assert_eq!(
+            DevAccount::Alice.account_id().to_string(),
+            "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY"
+        );
+        assert_eq!(
+            DevAccount::Bob.account_id().to_string(),
+            "5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty"
+        );
+        assert_eq!(
+            DevAccount::Alice.babe_id().to_raw_vec(),
+            DevAccount::Alice.sr25519().public().to_raw_vec()
+        );
+        assert_eq!(
+            DevAccount::Alice.grandpa_id().to_raw_vec(),
+            DevAccount::Alice.ed25519().public().to_raw_vec()
+        );
+        assert_ne!(
+            DevAccount::Alice.babe_id().to_raw_vec(),
+            DevAccount::Alice.grandpa_id().to_raw_vec()
+        );
+    }
+
+    #[test]
+    fn t_from_name() {
+        for account in DevAccount::ALL.iter() {
+            assert_eq!(DevAccount::from_name(account.name()), Some(*account));
+            assert_eq!(account.to_string().parse(), Ok(*account));
+        }
+        assert_eq!(DevAccount::from_name("ferdie"), Some(DevAccount::Ferdie));
+        assert_eq!(DevAccount::from_name("ALICE"), Some(DevAccount::Alice));
+        assert_eq!(DevAccount::from_name("Alicia"), None);
+        "".parse::<DevAccount>().unwrap_err();
+    }
+}
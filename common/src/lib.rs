@@ -1,14 +1,25 @@
+mod error;
+mod key;
+mod keyring;
+mod storage;
+
+pub use error::{Encoding, Error};
+pub use key::{parse_key, parse_ss58, SS58_PREFIX};
+pub use keyring::DevAccount;
+pub use storage::sudo_key_raw_key;
+
 use sr_primitives::AccountId32;
-use substrate_primitives::Public;
+use substrate_primitives::{sr25519, Public, H256};
 
-/// convert a 0x prefixed hex string to a 32 byte public key
-pub fn parse_pubkey<T: Public>(imp: &str) -> Result<T, &'static str> {
-    Ok(Public::from_slice(&parse_key_256(imp)?))
+/// convert a hex string, SS58 address, secret uri or dev account name to a 32 byte public key
+pub fn parse_pubkey<T: Public>(imp: &str) -> Result<T, Error> {
+    parse_key(imp)
 }
 
-/// convert a 0x prefixed hex string to an AccountId32
-pub fn parse_accountid32(imp: &str) -> Result<AccountId32, &'static str> {
-    parse_key_256(imp).map(Into::into)
+/// convert a hex string, SS58 address, secret uri or dev account name to an AccountId32, secret
+/// uris are derived as sr25519 keys
+pub fn parse_accountid32(imp: &str) -> Result<AccountId32, Error> {
+    parse_key::<sr25519::Public>(imp).map(|key| key.0.into())
 }
 
 /// panics if slice is wrong length
@@ -19,32 +30,80 @@ pub fn slice_to_arr32<T: Default + Copy>(src: &[T]) -> [T; 32] {
 }
 
 /// parse a 256 bit, 32 byte key from a 0x prefixed hex string
-pub fn parse_key_256(imp: &str) -> Result<[u8; 32], &'static str> {
+pub fn parse_key_256(imp: &str) -> Result<[u8; 32], Error> {
     let imp: &[u8] = imp.as_bytes();
 
     // check key is 0x prefixed, remove prefix
     let imp: &[u8] = if imp.starts_with(b"0x") {
         &imp[2..]
     } else {
-        return Err("public key should be prefixed with '0x'");
+        return Err(Error::MissingPrefix);
     };
 
     // check key is correct len
+    let wrong_length = Error::WrongLength {
+        encoding: Encoding::Hex,
+        expected: 64,
+        found: imp.len(),
+    };
     if imp.len() != 64 {
-        return Err("256 bit public key should be 64 hex digits");
+        return Err(wrong_length);
     }
 
-    // decode hex
-    let pk: Vec<u8> = hex::decode(imp).map_err(|err| {
-        use hex::FromHexError::*;
-        match err {
-            InvalidHexCharacter { .. } => "invalid hex character, must be [0-9][a-z][A-Z]",
-            OddLength => panic!("this should not happen"),
-            InvalidStringLength => panic!("this should not happen"),
-        }
+    // decode hex, positions are reported relative to the prefixed key
+    let pk: Vec<u8> = hex::decode(imp).map_err(|err| match err {
+        hex::FromHexError::InvalidHexCharacter { c, index } => Error::InvalidCharacter {
+            encoding: Encoding::Hex,
+            character: c,
+            position: index + 2,
+        },
+        hex::FromHexError::OddLength | hex::FromHexError::InvalidStringLength => wrong_length,
     })?;
 
-    assert_eq!(pk.len(), 32);
-
     Ok(slice_to_arr32(&pk))
 }
+
+/// parse a 0x prefixed, 32 byte hex hash, such as a block hash
+pub fn parse_hash(imp: &str) -> Result<H256, Error> {
+    parse_key_256(imp).map(H256::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn t_parse_key_256() {
+        let key = "0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d";
+        assert_eq!(parse_key_256(key).unwrap()[0], 0xd4);
+        assert_eq!(parse_key_256(&key[2..]), Err(Error::MissingPrefix));
+        assert_eq!(
+            parse_key_256(&key[..65]),
+            Err(Error::WrongLength {
+                encoding: Encoding::Hex,
+                expected: 64,
+                found: 63
+            })
+        );
+        let mut invalid = key.to_string();
+        invalid.replace_range(10..11, "g");
+        assert_eq!(
+            parse_key_256(&invalid),
+            Err(Error::InvalidCharacter {
+                encoding: Encoding::Hex,
+                character: 'g',
+                position: 10
+            })
+        );
+    }
+
+    #[test]
+    fn t_parse_hash() {
+        assert_eq!(
+            parse_hash("0x0000000000000000000000000000000000000000000000000000000000000001"),
+            Ok(H256::from_low_u64_be(1))
+        );
+        parse_hash("0x01").unwrap_err();
+        parse_hash("1").unwrap_err();
+    }
+}
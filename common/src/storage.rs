@@ -0,0 +1,10 @@
+//! Raw keys of storage items that their modules do not export.
+
+use substrate_primitives::twox_128;
+
+/// Raw key of the sudo module's `Key` value. The storage item is private to the module, so the key
+/// is derived the way `decl_storage` derives the key of a plain value: the twox128 hash of the
+/// module's storage prefix and the item's name.
+pub fn sudo_key_raw_key() -> Vec<u8> {
+    twox_128(b"Sudo Key").to_vec()
+}
@@ -1,5 +1,6 @@
 use codec::{Codec, Decode, Encode};
 use core::convert::TryInto;
+use rstd::collections::btree_map::BTreeMap;
 use rstd::prelude::*;
 #[cfg(feature = "std")]
 use serde::{Deserialize, Serialize};
@@ -14,6 +15,24 @@ pub trait Trait: system::Trait {
     type TokenBalance: Parameter + Member + SimpleArithmetic + Codec + Default + Copy;
 }
 
+// the limits are macros so the error messages of `init` can be built from them with `concat!`
+macro_rules! max_name_len {
+    () => {
+        64
+    };
+}
+macro_rules! max_ticker_len {
+    () => {
+        32
+    };
+}
+
+/// Maximum length of a token name in bytes.
+pub const MAX_NAME_LEN: usize = max_name_len!();
+
+/// Maximum length of a token ticker in bytes.
+pub const MAX_TICKER_LEN: usize = max_ticker_len!();
+
 // struct to store the token details
 #[derive(Encode, Decode, Default, Clone, PartialEq, Debug)]
 #[cfg_attr(feature = "std", derive(Serialize, Deserialize))]
@@ -41,8 +60,8 @@ decl_module! {
 
             // checking max size for name and ticker
             // byte arrays (vecs) with no max size should be avoided
-            ensure!(name.len() <= 64, "token name cannot exceed 64 bytes");
-            ensure!(ticker.len() <= 32, "token ticker cannot exceed 32 bytes");
+            ensure!(name.len() <= MAX_NAME_LEN, concat!("token name cannot exceed ", max_name_len!(), " bytes"));
+            ensure!(ticker.len() <= MAX_TICKER_LEN, concat!("token ticker cannot exceed ", max_ticker_len!(), " bytes"));
 
             let token_id = Self::token_id();
             let next_token_id = token_id.checked_add(1).ok_or("overflow in calculating next token id")?;
@@ -154,17 +173,32 @@ decl_storage! {
             })
             : map u32 => Erc20Token<T::TokenBalance>;
         // balances mapping for an account and token
+        // each genesis token's supply goes to its owner, minus any initial allocations
         pub BalanceOf get(balance_of)
             build(|config: &GenesisConfig<T>| -> Vec<_> {
-                config
-                    .initial_tokens
-                    .iter()
-                    .cloned()
-                    .enumerate()
-                    .map(|(id, (token_details, account))| {
-                        ((id.try_into().expect("too many tokens"), account), token_details.total_supply)
-                    })
-                    .collect()
+                let mut balances = BTreeMap::new();
+                for (id, (token_details, owner)) in config.initial_tokens.iter().enumerate() {
+                    let id: u32 = id.try_into().expect("too many tokens");
+                    balances.insert((id, owner.clone()), token_details.total_supply);
+                }
+                for (id, account, value) in config.initial_allocations.iter() {
+                    let (_, owner) = config
+                        .initial_tokens
+                        .get(*id as usize)
+                        .expect("genesis allocation of a token that does not exist");
+                    assert!(account != owner, "genesis allocation to the holder of the token");
+                    let owner_balance = balances
+                        .get_mut(&(*id, owner.clone()))
+                        .expect("owner balance was inserted above");
+                    *owner_balance = owner_balance
+                        .checked_sub(value)
+                        .expect("genesis allocations exceed token supply");
+                    let balance = balances.entry((*id, account.clone())).or_default();
+                    *balance = balance
+                        .checked_add(value)
+                        .expect("genesis allocations exceed token supply");
+                }
+                balances.into_iter().collect()
             })
             : map (u32, T::AccountId) => T::TokenBalance;
         // allowance for an account and token
@@ -172,7 +206,12 @@ decl_storage! {
     }
 
     add_extra_genesis {
+        // tokens to mint, with the account that receives the total supply
         config(initial_tokens): Vec<(Erc20Token<T::TokenBalance>, T::AccountId)>;
+        // (token_id, account, value), value is moved from the token's owner to account
+        // optional so chainspecs written before allocations existed still load
+        #[serde(default)]
+        config(initial_allocations): Vec<(u32, T::AccountId, T::TokenBalance)>;
     }
 }
 
@@ -234,50 +273,13 @@ impl<T: Trait> Module<T> {
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::erc20;
+    use test_support::{assert_last_event, events, run_to_block, GenesisBuilder};
+
+    test_support::mock_runtime!(Test; erc20);
 
-    use primitives::H256;
-    use sr_primitives::weights::Weight;
-    use sr_primitives::Perbill;
-    use sr_primitives::{
-        testing::Header,
-        traits::{BlakeTwo256, IdentityLookup},
-    };
-    use support::{impl_outer_origin, parameter_types};
-
-    impl_outer_origin! {
-        pub enum Origin for Test {}
-    }
-
-    // For testing the module, we construct most of a mock runtime. This means
-    // first constructing a configuration type (`Test`) which `impl`s each of the
-    // configuration traits of modules we want to use.
-    #[derive(Clone, Eq, PartialEq)]
-    pub struct Test;
-    parameter_types! {
-        pub const BlockHashCount: u64 = 250;
-        pub const MaximumBlockWeight: Weight = 1024;
-        pub const MaximumBlockLength: u32 = 2 * 1024;
-        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
-    }
-    impl system::Trait for Test {
-        type Origin = Origin;
-        type Call = ();
-        type Index = u64;
-        type BlockNumber = u64;
-        type Hash = H256;
-        type Hashing = BlakeTwo256;
-        type AccountId = u64;
-        type Lookup = IdentityLookup<Self::AccountId>;
-        type Header = Header;
-        type Event = ();
-        type BlockHashCount = BlockHashCount;
-        type MaximumBlockWeight = MaximumBlockWeight;
-        type MaximumBlockLength = MaximumBlockLength;
-        type AvailableBlockRatio = AvailableBlockRatio;
-        type Version = ();
-    }
     impl Trait for Test {
-        type Event = ();
+        type Event = Event;
         type TokenBalance = u128;
     }
     type TemplateModule = Module<Test>;
@@ -288,20 +290,19 @@ mod test {
     const C: u64 = 2;
 
     fn new_test_ext() -> runtime_io::TestExternalities {
-        system::GenesisConfig::default()
-            .build_storage::<Test>()
-            .unwrap()
-            .into()
+        test_support::new_test_ext::<Test>()
     }
 
     /// create test env with some tokens pre-inited by the chainspec
     fn pre_alloc_ext(
         initial_tokens: Vec<(Erc20Token<u128>, u64)>,
     ) -> runtime_io::TestExternalities {
-        GenesisConfig::<Test> { initial_tokens }
-            .build_storage()
-            .unwrap()
-            .into()
+        GenesisBuilder::<Test>::new()
+            .with(GenesisConfig::<Test> {
+                initial_tokens,
+                initial_allocations: vec![],
+            })
+            .build()
     }
 
     /// send tokens from A to B
@@ -336,6 +337,22 @@ mod test {
         });
     }
 
+    #[test]
+    fn init_limits() {
+        new_test_ext().execute_with(|| {
+            let long_name = vec![b'a'; MAX_NAME_LEN + 1];
+            let long_ticker = vec![b'a'; MAX_TICKER_LEN + 1];
+            assert_eq!(
+                TemplateModule::init(Origin::ROOT, A, long_name, b"TRS".to_vec(), 10),
+                Err("token name cannot exceed 64 bytes")
+            );
+            assert_eq!(
+                TemplateModule::init(Origin::ROOT, A, b"Trash".to_vec(), long_ticker, 10),
+                Err("token ticker cannot exceed 32 bytes")
+            );
+        });
+    }
+
     #[test]
     fn transfer_pong() {
         new_test_ext().execute_with(|| {
@@ -547,6 +564,88 @@ mod test {
         });
     }
 
+    #[test]
+    fn genesis_config_allocations() {
+        let conf = GenesisConfig::<Test> {
+            initial_tokens: vec![
+                (
+                    Erc20Token {
+                        name: b"token 0".to_vec(),
+                        ticker: b"token ticker 0".to_vec(),
+                        total_supply: 10,
+                    },
+                    A,
+                ),
+                (
+                    Erc20Token {
+                        name: b"token 1".to_vec(),
+                        ticker: b"token ticker 1".to_vec(),
+                        total_supply: 10,
+                    },
+                    B,
+                ),
+            ],
+            initial_allocations: vec![(0, B, 3), (0, C, 7), (1, A, 1), (1, B, 1)],
+        };
+        let mut ext = GenesisBuilder::<Test>::new().with(conf).build();
+        ext.execute_with(|| {
+            assert_eq!(TemplateModule::balance_of((0, A)), 0);
+            assert_eq!(TemplateModule::balance_of((0, B)), 3);
+            assert_eq!(TemplateModule::balance_of((0, C)), 7);
+            assert_eq!(TemplateModule::balance_of((1, A)), 1);
+            assert_eq!(TemplateModule::balance_of((1, B)), 9);
+            assert_eq!(TemplateModule::balance_of((1, C)), 0);
+            assert_eq!(TemplateModule::token_details(0).total_supply, 10);
+        });
+    }
+
+    #[test]
+    #[should_panic(expected = "genesis allocations exceed token supply")]
+    fn genesis_config_allocations_exceed_supply() {
+        GenesisConfig::<Test> {
+            initial_tokens: vec![(
+                Erc20Token {
+                    name: b"token 0".to_vec(),
+                    ticker: b"token ticker 0".to_vec(),
+                    total_supply: 10,
+                },
+                A,
+            )],
+            initial_allocations: vec![(0, B, 6), (0, C, 6)],
+        }
+        .build_storage()
+        .unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "genesis allocation to the holder of the token")]
+    fn genesis_config_allocation_to_holder() {
+        GenesisConfig::<Test> {
+            initial_tokens: vec![(
+                Erc20Token {
+                    name: b"token 0".to_vec(),
+                    ticker: b"token ticker 0".to_vec(),
+                    total_supply: 10,
+                },
+                A,
+            )],
+            initial_allocations: vec![(0, A, 1)],
+        }
+        .build_storage()
+        .unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "genesis allocation of a token that does not exist")]
+    fn genesis_config_allocation_unknown_token() {
+        GenesisConfig::<Test> {
+            initial_tokens: vec![],
+            initial_allocations: vec![(0, B, 0)],
+        }
+        .build_storage()
+        .unwrap();
+    }
+
     #[test]
     fn must_root() {
         new_test_ext().execute_with(|| {
@@ -733,8 +832,36 @@ mod test {
     }
 
     #[test]
-    #[ignore] // not yet implemented
     fn events() {
-        unimplemented!();
+        new_test_ext().execute_with(|| {
+            run_to_block::<Test, TemplateModule>(1);
+
+            // minting is not announced
+            TemplateModule::init(Origin::ROOT, A, b"Trash".to_vec(), b"TRS".to_vec(), 10).unwrap();
+            assert_eq!(events::<Test>(), vec![]);
+
+            TemplateModule::transfer(Origin::signed(A), 0, B, 4).unwrap();
+            assert_last_event::<Test>(RawEvent::Transfer(0, A, B, 4));
+
+            TemplateModule::approve(Origin::signed(A), 0, C, 3).unwrap();
+            assert_last_event::<Test>(RawEvent::Approval(0, A, C, 3));
+
+            TemplateModule::transfer_from(Origin::signed(C), 0, A, C, 2).unwrap();
+            assert_eq!(
+                events::<Test>()[2..],
+                [
+                    Event::erc20(RawEvent::Transfer(0, A, C, 2)),
+                    Event::erc20(RawEvent::Approval(0, A, C, 2)),
+                ]
+            );
+
+            TemplateModule::burn(Origin::signed(B), 0, 1).unwrap();
+            assert_last_event::<Test>(RawEvent::Burn(0, B, 1));
+
+            // failed calls deposit nothing
+            TemplateModule::transfer(Origin::signed(C), 0, A, 100).unwrap_err();
+            TemplateModule::burn(Origin::signed(C), 0, 100).unwrap_err();
+            assert_eq!(events::<Test>().len(), 5);
+        });
     }
 }
@@ -6,6 +6,6 @@ mod erc20;
 pub use crate::erc20::GenesisConfig;
 
 pub use crate::erc20::{
-    Allowance, BalanceOf, Erc20Token, Event, Module, TokenId, Tokens, Trait,
-    __InherentHiddenInstance,
+    Allowance, BalanceOf, Call, Erc20Token, Event, Module, RawEvent, TokenId, Tokens, Trait,
+    MAX_NAME_LEN, MAX_TICKER_LEN, __InherentHiddenInstance,
 };
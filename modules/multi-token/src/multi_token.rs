@@ -76,50 +76,13 @@ decl_event!(
 #[cfg(test)]
 mod test {
     use super::*;
+    use crate::multi_token;
     use codec::{Decode, Encode};
-    use primitives::H256;
-    use sr_primitives::weights::Weight;
-    use sr_primitives::Perbill;
-    use sr_primitives::{
-        testing::Header,
-        traits::{BlakeTwo256, IdentityLookup},
-    };
-    use support::{impl_outer_origin, parameter_types};
 
-    impl_outer_origin! {
-        pub enum Origin for Test {}
-    }
+    test_support::mock_runtime!(Test; multi_token);
 
-    // For testing the module, we construct most of a mock runtime. This means
-    // first constructing a configuration type (`Test`) which `impl`s each of the
-    // configuration traits of modules we want to use.
-    #[derive(Clone, Eq, PartialEq)]
-    pub struct Test;
-    parameter_types! {
-        pub const BlockHashCount: u64 = 250;
-        pub const MaximumBlockWeight: Weight = 1024;
-        pub const MaximumBlockLength: u32 = 2 * 1024;
-        pub const AvailableBlockRatio: Perbill = Perbill::from_percent(75);
-    }
-    impl system::Trait for Test {
-        type Origin = Origin;
-        type Call = ();
-        type Index = u64;
-        type BlockNumber = u64;
-        type Hash = H256;
-        type Hashing = BlakeTwo256;
-        type AccountId = u64;
-        type Lookup = IdentityLookup<Self::AccountId>;
-        type Header = Header;
-        type Event = ();
-        type BlockHashCount = BlockHashCount;
-        type MaximumBlockWeight = MaximumBlockWeight;
-        type MaximumBlockLength = MaximumBlockLength;
-        type AvailableBlockRatio = AvailableBlockRatio;
-        type Version = ();
-    }
     impl Trait for Test {
-        type Event = ();
+        type Event = Event;
         type TokenBalance = u128;
         type Discriminant = TokenType;
     }
@@ -142,13 +105,8 @@ mod test {
     const B: u64 = 1;
     const C: u64 = 2;
 
-    // This function basically just builds a genesis storage key/value store according to
-    // our desired mockup.
     fn new_test_ext() -> runtime_io::TestExternalities {
-        system::GenesisConfig::default()
-            .build_storage::<Test>()
-            .unwrap()
-            .into()
+        test_support::new_test_ext::<Test>()
     }
 
     // set account balance of bene for token_id to total_supply
@@ -173,6 +131,16 @@ mod test {
         });
     }
 
+    #[test]
+    fn xfer_event() {
+        new_test_ext().execute_with(|| {
+            test_support::run_to_block::<Test, TemplateModule>(1);
+            cheat_in(A, TokenType::B, 10);
+            TemplateModule::transfer(Origin::signed(A), B, TokenType::B, 4).unwrap();
+            test_support::assert_last_event::<Test>(RawEvent::Transfer(A, B, TokenType::B, 4));
+        });
+    }
+
     #[test]
     fn cheat_in_meta() {
         new_test_ext().execute_with(|| {
@@ -0,0 +1,183 @@
+//! Mock runtime shared by the tests of our srml modules.
+//!
+//! `mock_runtime!` declares a `Test` runtime with `u64` accounts and block numbers, along with
+//! its `Origin`, an `Event` collecting the events of the listed modules, and `System`. The
+//! module's own trait is left for the test to implement. The system parameters default to the
+//! values of the srml examples and can be overridden after the module list:
+//!
+//! ```ignore
+//! use crate::erc20;
+//!
+//! // or: test_support::mock_runtime!(Test; erc20 { AvailableBlockRatio: Perbill::one(), });
+//!
+//! test_support::mock_runtime!(Test; erc20);
+//!
+//! impl erc20::Trait for Test {
+//!     type Event = Event;
+//!     type TokenBalance = u128;
+//! }
+//!
+//! #[test]
+//! fn transfer() {
+//!     test_support::new_test_ext::<Test>().execute_with(|| {
+//!         test_support::run_to_block::<Test, erc20::Module<Test>>(1);
+//!         ...
+//!         test_support::assert_last_event::<Test>(erc20::RawEvent::Transfer(0, 1, 2, 10));
+//!     });
+//! }
+//! ```
+
+pub use sr_io;
+pub use sr_primitives;
+pub use srml_support as support;
+pub use srml_system as system;
+pub use substrate_primitives as primitives;
+
+use core::marker::PhantomData;
+use sr_io::TestExternalities;
+use sr_primitives::traits::{OnFinalize, OnInitialize, One, Zero};
+use sr_primitives::{BuildStorage, ChildrenStorageOverlay, StorageOverlay};
+
+/// Declare a mock runtime named `$runtime` whose `Event` has a variant for each listed module.
+/// Modules are named as in `impl_outer_event!`, by a module in scope that defines `Event<T>`.
+/// Like the srml macros it wraps, it expects `srml-system` to be in scope as `system`.
+///
+/// Any of `BlockHashCount`, `MaximumBlockWeight`, `MaximumBlockLength` and `AvailableBlockRatio`
+/// may be overridden in braces after the module list. Overrides are given in that order, each
+/// followed by a comma.
+#[macro_export]
+macro_rules! mock_runtime {
+    (@or $default:expr) => {
+        $default
+    };
+    (@or $default:expr, $value:expr) => {
+        $value
+    };
+    (
+        $runtime:ident $(; $($module:ident),* $(,)?)?
+        $({
+            $(BlockHashCount: $block_hash_count:expr,)?
+            $(MaximumBlockWeight: $maximum_block_weight:expr,)?
+            $(MaximumBlockLength: $maximum_block_length:expr,)?
+            $(AvailableBlockRatio: $available_block_ratio:expr,)?
+        })?
+    ) => {
+        $crate::support::impl_outer_origin! {
+            pub enum Origin for $runtime {}
+        }
+
+        $crate::support::impl_outer_event! {
+            pub enum Event for $runtime {
+                $($($module<T>,)*)?
+            }
+        }
+
+        #[derive(Clone, Eq, PartialEq, Debug)]
+        pub struct $runtime;
+
+        $crate::support::parameter_types! {
+            pub const BlockHashCount: u64 =
+                $crate::mock_runtime!(@or 250 $($(, $block_hash_count)?)?);
+            pub const MaximumBlockWeight: $crate::sr_primitives::weights::Weight =
+                $crate::mock_runtime!(@or 1024 $($(, $maximum_block_weight)?)?);
+            pub const MaximumBlockLength: u32 =
+                $crate::mock_runtime!(@or 2 * 1024 $($(, $maximum_block_length)?)?);
+            pub const AvailableBlockRatio: $crate::sr_primitives::Perbill = $crate::mock_runtime!(
+                @or $crate::sr_primitives::Perbill::from_percent(75)
+                $($(, $available_block_ratio)?)?
+            );
+        }
+
+        impl $crate::system::Trait for $runtime {
+            type Origin = Origin;
+            type Call = ();
+            type Index = u64;
+            type BlockNumber = u64;
+            type Hash = $crate::primitives::H256;
+            type Hashing = $crate::sr_primitives::traits::BlakeTwo256;
+            type AccountId = u64;
+            type Lookup = $crate::sr_primitives::traits::IdentityLookup<Self::AccountId>;
+            type Header = $crate::sr_primitives::testing::Header;
+            type Event = Event;
+            type BlockHashCount = BlockHashCount;
+            type MaximumBlockWeight = MaximumBlockWeight;
+            type MaximumBlockLength = MaximumBlockLength;
+            type AvailableBlockRatio = AvailableBlockRatio;
+            type Version = ();
+        }
+
+        pub type System = $crate::system::Module<$runtime>;
+    };
+}
+
+/// Genesis storage built from the default system genesis plus the genesis config of any module.
+pub struct GenesisBuilder<T> {
+    storage: (StorageOverlay, ChildrenStorageOverlay),
+    _runtime: PhantomData<T>,
+}
+
+impl<T: system::Trait> Default for GenesisBuilder<T> {
+    fn default() -> Self {
+        GenesisBuilder {
+            storage: system::GenesisConfig::default()
+                .build_storage::<T>()
+                .expect("default system genesis is valid"),
+            _runtime: PhantomData,
+        }
+    }
+}
+
+impl<T: system::Trait> GenesisBuilder<T> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a module's genesis config. Panics if the module rejects it.
+    pub fn with(mut self, config: impl BuildStorage) -> Self {
+        config
+            .assimilate_storage(&mut self.storage)
+            .expect("invalid genesis config");
+        self
+    }
+
+    pub fn build(self) -> TestExternalities {
+        self.storage.into()
+    }
+}
+
+/// Externalities holding only the default system genesis.
+pub fn new_test_ext<T: system::Trait>() -> TestExternalities {
+    GenesisBuilder::<T>::new().build()
+}
+
+/// Advance to block `n`, finalizing each block and initializing the next the way the executive
+/// would. `M` is the module, or tuple of modules, whose hooks are called. Events are only
+/// recorded after genesis, so tests that check events should start with `run_to_block(1)`.
+pub fn run_to_block<T, M>(n: T::BlockNumber)
+where
+    T: system::Trait,
+    M: OnInitialize<T::BlockNumber> + OnFinalize<T::BlockNumber>,
+{
+    let mut number = system::Module::<T>::block_number();
+    while number < n {
+        if !number.is_zero() {
+            M::on_finalize(number);
+        }
+        number = number + One::one();
+        system::Module::<T>::set_block_number(number);
+        M::on_initialize(number);
+    }
+}
+
+/// Events deposited so far, oldest first.
+pub fn events<T: system::Trait>() -> Vec<T::Event> {
+    system::Module::<T>::events()
+        .into_iter()
+        .map(|record| record.event)
+        .collect()
+}
+
+/// Panics unless `event` is the most recently deposited event.
+pub fn assert_last_event<T: system::Trait>(event: impl Into<T::Event>) {
+    assert_eq!(events::<T>().last(), Some(&event.into()));
+}
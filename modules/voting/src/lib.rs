@@ -42,79 +42,31 @@ pub use crate::voting::{
 mod tests {
     use super::*;
     use codec::Encode;
-    use primitives::H256;
     use rstd::prelude::*;
     use rstd::result;
+    use runtime_primitives::traits::{BlakeTwo256, Hash};
+    use runtime_support::assert_ok;
     use runtime_support::dispatch::Result;
     use system::{EventRecord, Phase};
-    // The testing primitives are very useful for avoiding having to work with signatures
-    // or public keys. `u64` is used as the `AccountId` and no `Signature`s are requried.
-    use runtime_primitives::{
-        testing::Header,
-        traits::{BlakeTwo256, Hash, IdentityLookup},
-        Perbill,
-    };
-
-    use runtime_support::{assert_ok, impl_outer_origin};
 
     static SECRET: [u8; 32] = [
         1, 0, 1, 0, 1, 0, 1, 0, 1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0,
         0, 4,
     ];
 
-    impl_outer_origin! {
-        pub enum Origin for Test {}
-    }
-
-    impl_outer_event! {
-        pub enum Event for Test {
-            voting<T>,
-        }
-    }
-
-    #[derive(Clone, PartialEq, Eq, Debug)]
-    pub struct Test;
-
-    parameter_types! {
-        pub const BlockHashCount: u64 = 250;
-        pub const MaximumBlockWeight: u32 = 1024;
-        pub const MaximumBlockLength: u32 = 2 * 1024;
-        pub const AvailableBlockRatio: Perbill = Perbill::one();
-    }
-
-    impl system::Trait for Test {
-        type Origin = Origin;
-        type Call = ();
-        type Index = u64;
-        type BlockNumber = u64;
-        type Hash = H256;
-        type Hashing = BlakeTwo256;
-        type AccountId = u64;
-        type Lookup = IdentityLookup<Self::AccountId>;
-        type Header = Header;
-        type Event = Event;
-        type BlockHashCount = BlockHashCount;
-        type MaximumBlockWeight = MaximumBlockWeight;
-        type MaximumBlockLength = MaximumBlockLength;
-        type AvailableBlockRatio = AvailableBlockRatio;
-        type Version = ();
-    }
+    // the voting tests have always run with the whole block available to extrinsics
+    test_support::mock_runtime!(Test; voting {
+        AvailableBlockRatio: runtime_primitives::Perbill::one(),
+    });
 
     impl Trait for Test {
         type Event = Event;
     }
 
-    pub type System = system::Module<Test>;
     pub type Voting = Module<Test>;
 
-    // This function basically just builds a genesis storage key/value store according to
-    // our desired mockup.
     fn new_test_ext() -> sr_io::TestExternalities {
-        let t = system::GenesisConfig::default()
-            .build_storage::<Test>()
-            .unwrap();
-        // We use default for brevity, but you can configure as desired if needed.
-        t.into()
+        test_support::new_test_ext::<Test>()
     }
 
     fn create_vote(
@@ -0,0 +1,215 @@
+//! Bulk genesis allocations read from csv files.
+//!
+//! Native balances are listed as `account,amount` and erc20 allocations as
+//! `token,account,amount`, where `token` is the ticker of a token minted at genesis. Blank lines
+//! and lines starting with `#` are ignored, as is a header row naming the columns.
+
+use crate::token::{Allocation, TokenSpec};
+use sr_primitives::AccountId32;
+use std::collections::BTreeSet;
+use std::path::Path;
+use substrate_warmup_common::parse_accountid32;
+
+/// Read native balances from an `account,amount` csv file.
+pub fn read_balances(path: &Path) -> Result<Vec<(AccountId32, u128)>, String> {
+    rows(path, &["account", "amount"])?
+        .into_iter()
+        .map(|(line, row)| {
+            let at = |e: String| format!("{}:{}: {}", path.display(), line, e);
+            Ok((account(&row[0]).map_err(at)?, amount(&row[1]).map_err(at)?))
+        })
+        .collect()
+}
+
+/// Read erc20 allocations from a `token,account,amount` csv file.
+pub fn read_token_allocations(path: &Path) -> Result<Vec<(String, Allocation)>, String> {
+    rows(path, &["token", "account", "amount"])?
+        .into_iter()
+        .map(|(line, row)| {
+            let at = |e: String| format!("{}:{}: {}", path.display(), line, e);
+            Ok((
+                row[0].clone(),
+                Allocation {
+                    account: account(&row[1]).map_err(at)?,
+                    amount: amount(&row[2]).map_err(at)?,
+                },
+            ))
+        })
+        .collect()
+}
+
+/// Add allocations to the tokens they name. Duplicates and allocations exceeding a token's supply
+/// are left for `check_tokens` to report.
+pub fn add_token_allocations(
+    tokens: &mut [TokenSpec],
+    allocations: Vec<(String, Allocation)>,
+) -> Result<(), String> {
+    for (ticker, allocation) in allocations {
+        tokens
+            .iter_mut()
+            .find(|token| token.ticker == ticker)
+            .ok_or_else(|| format!("allocation of unknown token {:?}", ticker))?
+            .allocations
+            .push(allocation);
+    }
+    Ok(())
+}
+
+/// Reject native balances that endow the same account more than once.
+pub fn check_balances(balances: &[(AccountId32, u128)]) -> Result<(), String> {
+    let mut accounts = BTreeSet::new();
+    for (account, _) in balances {
+        if !accounts.insert(account) {
+            return Err(format!("{} is endowed more than once", account));
+        }
+    }
+    Ok(())
+}
+
+/// Non-empty rows of a csv file along with their line numbers. Each row has exactly as many
+/// fields as `header`.
+fn rows(path: &Path, header: &[&str]) -> Result<Vec<(usize, Vec<String>)>, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("could not read {}: {}", path.display(), e))?;
+    let mut rows = Vec::new();
+    for (index, line) in contents.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let row: Vec<String> = line.split(',').map(|f| f.trim().to_string()).collect();
+        if rows.is_empty() && row.iter().map(String::as_str).eq(header.iter().cloned()) {
+            continue;
+        }
+        if row.len() != header.len() {
+            return Err(format!(
+                "{}:{}: expected {} fields, formatted as {}",
+                path.display(),
+                line_number,
+                header.len(),
+                header.join(",")
+            ));
+        }
+        rows.push((line_number, row));
+    }
+    Ok(rows)
+}
+
+fn account(imp: &str) -> Result<AccountId32, String> {
+    parse_accountid32(imp).map_err(|e| format!("invalid account {:?}: {}", imp, e))
+}
+
+fn amount(imp: &str) -> Result<u128, String> {
+    imp.parse()
+        .map_err(|e| format!("invalid amount {:?}: {}", imp, e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain_spec::tests::temp_file;
+    use crate::token::check_tokens;
+    use std::path::PathBuf;
+    use substrate_warmup_common::DevAccount;
+
+    fn write(name: &str, contents: &str) -> PathBuf {
+        temp_file(&format!("{}.csv", name), contents)
+    }
+
+    #[test]
+    fn t_read_balances() {
+        let path = write(
+            "t_read_balances",
+            "account,amount\n\
+             # partners\n\
+             //Alice, 10\n\
+             \n\
+             5FHneW46xGXgs5mUiveU4sbTyGBzmstUspZC92UhjJM694ty,20\n",
+        );
+        assert_eq!(
+            read_balances(&path).unwrap(),
+            vec![
+                (DevAccount::Alice.account_id(), 10),
+                (DevAccount::Bob.account_id(), 20)
+            ]
+        );
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn t_read_balances_invalid() {
+        for (name, contents, err) in &[
+            ("t_rbi_fields", "//Alice,10,3\n", ":1: expected 2 fields"),
+            (
+                "t_rbi_account",
+                "\n//Alice,10\nAlice,10\n",
+                ":3: invalid account",
+            ),
+            ("t_rbi_amount", "//Alice,-10\n", ":1: invalid amount"),
+        ] {
+            let path = write(name, contents);
+            let e = read_balances(&path).unwrap_err();
+            assert!(e.contains(err), "{:?} does not contain {:?}", e, err);
+            std::fs::remove_file(path).unwrap();
+        }
+        read_balances(Path::new("this/file/does/not/exist.csv")).unwrap_err();
+    }
+
+    #[test]
+    fn t_check_balances() {
+        let alice = DevAccount::Alice.account_id();
+        let bob = DevAccount::Bob.account_id();
+        check_balances(&[(alice.clone(), 1), (bob, 1)]).unwrap();
+        check_balances(&[(alice.clone(), 1), (alice, 2)]).unwrap_err();
+    }
+
+    #[test]
+    fn t_token_allocations() {
+        let path = write(
+            "t_token_allocations",
+            "token,account,amount\nPSTABLE1,//Bob,10\nPSTABLE1,//Charlie,20\n",
+        );
+        let mut tokens = vec![TokenSpec {
+            name: "PSTABLE1".into(),
+            ticker: "PSTABLE1".into(),
+            total_supply: 30,
+            holder: DevAccount::Alice.account_id(),
+            allocations: vec![],
+        }];
+        add_token_allocations(&mut tokens, read_token_allocations(&path).unwrap()).unwrap();
+        assert_eq!(tokens[0].allocations.len(), 2);
+        check_tokens(&tokens).unwrap();
+
+        // the supply is now fully allocated
+        let mut over = tokens.clone();
+        add_token_allocations(
+            &mut over,
+            vec![("PSTABLE1".into(), allocation(DevAccount::Dave, 1))],
+        )
+        .unwrap();
+        check_tokens(&over).unwrap_err();
+
+        let mut duplicate = tokens.clone();
+        add_token_allocations(
+            &mut duplicate,
+            vec![("PSTABLE1".into(), allocation(DevAccount::Bob, 0))],
+        )
+        .unwrap();
+        check_tokens(&duplicate).unwrap_err();
+
+        add_token_allocations(
+            &mut tokens,
+            vec![("PSTABLE2".into(), allocation(DevAccount::Bob, 1))],
+        )
+        .unwrap_err();
+        std::fs::remove_file(path).unwrap();
+    }
+
+    fn allocation(account: DevAccount, amount: u128) -> Allocation {
+        Allocation {
+            account: account.account_id(),
+            amount,
+        }
+    }
+}
@@ -0,0 +1,292 @@
+//! Programmatic construction of chainspecs. Every chain chaingen can output is built through
+//! `ChainSpecBuilder`, so specs built from rust pass the same checks as those built on the command
+//! line.
+//!
+//! ```no_run
+//! use substrate_warmup_chaingen::{dev_validator, ChainSpecBuilder, DevAccount};
+//!
+//! let spec = ChainSpecBuilder::new("My Testnet", "my-testnet")
+//!     .authority(dev_validator(DevAccount::Alice))
+//!     .sudo(DevAccount::Alice.account_id())
+//!     .balance(DevAccount::Alice.account_id(), 1_000_000_000_000)
+//!     .build()
+//!     .unwrap();
+//! println!("{}", spec.to_json(true).unwrap());
+//! ```
+
+use crate::allocation::check_balances;
+use crate::network::{ChainProperties, Network, Telemetry};
+use crate::node_key::BootnodeKey;
+use crate::token::{check_tokens, erc20_genesis, TokenSpec};
+use crate::validate::validate_genesis;
+use crate::validator::{check_validators, Validator};
+use crate::vesting::{balances_vesting, check_vesting, Vesting};
+use node_template_runtime::{
+    BabeConfig, BalancesConfig, GenesisConfig, GrandpaConfig, SudoConfig, SystemConfig, WASM_BINARY,
+};
+use sr_primitives::AccountId32;
+use substrate_chain_spec::ChainSpec;
+
+#[derive(Clone, Debug)]
+pub struct ChainSpecBuilder {
+    name: String,
+    id: String,
+    protocol_id: Option<String>,
+    authorities: Vec<Validator>,
+    sudo: Option<AccountId32>,
+    balances: Vec<(AccountId32, u128)>,
+    vesting: Vec<Vesting>,
+    tokens: Vec<TokenSpec>,
+    network: Network,
+    code: Option<Vec<u8>>,
+}
+
+impl ChainSpecBuilder {
+    /// Start a chain with a human readable name and a machine readable id.
+    pub fn new(name: &str, id: &str) -> Self {
+        ChainSpecBuilder {
+            name: name.to_string(),
+            id: id.to_string(),
+            protocol_id: None,
+            authorities: vec![],
+            sudo: None,
+            balances: vec![],
+            vesting: vec![],
+            tokens: vec![],
+            network: Network::default(),
+            code: None,
+        }
+    }
+
+    /// Network protocol id, used to keep peers of different chains apart.
+    pub fn protocol_id(mut self, protocol_id: &str) -> Self {
+        self.protocol_id = Some(protocol_id.to_string());
+        self
+    }
+
+    /// Add a babe and grandpa authority.
+    pub fn authority(mut self, authority: Validator) -> Self {
+        self.authorities.push(authority);
+        self
+    }
+
+    pub fn authorities(mut self, authorities: impl IntoIterator<Item = Validator>) -> Self {
+        self.authorities.extend(authorities);
+        self
+    }
+
+    /// Set the superuser account. Required.
+    pub fn sudo(mut self, sudo: AccountId32) -> Self {
+        self.sudo = Some(sudo);
+        self
+    }
+
+    /// Endow an account with native currency.
+    pub fn balance(mut self, account: AccountId32, amount: u128) -> Self {
+        self.balances.push((account, amount));
+        self
+    }
+
+    pub fn balances(mut self, balances: impl IntoIterator<Item = (AccountId32, u128)>) -> Self {
+        self.balances.extend(balances);
+        self
+    }
+
+    /// Lock part of an endowed account's balance, see `Vesting`.
+    pub fn vesting(mut self, vesting: impl IntoIterator<Item = Vesting>) -> Self {
+        self.vesting.extend(vesting);
+        self
+    }
+
+    /// Mint an erc20 token at genesis. Token ids are assigned in the order tokens are added.
+    pub fn token(mut self, token: TokenSpec) -> Self {
+        self.tokens.push(token);
+        self
+    }
+
+    pub fn tokens(mut self, tokens: impl IntoIterator<Item = TokenSpec>) -> Self {
+        self.tokens.extend(tokens);
+        self
+    }
+
+    /// Add the multiaddr of a node new nodes should connect to.
+    pub fn bootnode(mut self, bootnode: &str) -> Self {
+        self.network.bootnodes.push(bootnode.to_string());
+        self
+    }
+
+    pub fn bootnodes(mut self, bootnodes: impl IntoIterator<Item = String>) -> Self {
+        self.network.bootnodes.extend(bootnodes);
+        self
+    }
+
+    /// Add a bootnode identified by a node key file, see `BootnodeKey`.
+    pub fn bootnode_key(mut self, key: BootnodeKey) -> Self {
+        self.network.bootnode_keys.push(key);
+        self
+    }
+
+    /// Bootnodes that still need their node key read or generated.
+    pub fn bootnode_keys(&self) -> &[BootnodeKey] {
+        &self.network.bootnode_keys
+    }
+
+    pub fn telemetry(mut self, telemetry: impl IntoIterator<Item = Telemetry>) -> Self {
+        self.network.telemetry.extend(telemetry);
+        self
+    }
+
+    /// Token symbol, token decimals and ss58 format shown by wallets.
+    pub fn properties(mut self, properties: ChainProperties) -> Self {
+        self.network.properties = properties;
+        self
+    }
+
+    /// Bootnodes, telemetry and properties at once.
+    pub fn network(mut self, network: Network) -> Self {
+        self.network = network;
+        self
+    }
+
+    /// Runtime wasm to embed instead of the runtime compiled into chaingen, see
+    /// `wasm::load_wasm`.
+    pub fn wasm(mut self, code: Vec<u8>) -> Self {
+        self.code = Some(code);
+        self
+    }
+
+    /// The genesis config described so far, without any checks.
+    pub fn genesis(&self) -> Result<GenesisConfig, String> {
+        let sudo = self.sudo.clone().ok_or("no sudo key was set")?;
+        Ok(GenesisConfig {
+            system: Some(SystemConfig {
+                code: self.code.clone().unwrap_or_else(|| WASM_BINARY.to_vec()),
+                changes_trie_config: Default::default(),
+            }),
+            balances: Some(BalancesConfig {
+                balances: self.balances.clone(),
+                vesting: balances_vesting(&self.vesting),
+            }),
+            sudo: Some(SudoConfig { key: sudo }),
+            babe: Some(BabeConfig {
+                authorities: self
+                    .authorities
+                    .iter()
+                    .map(|v| (v.babe.clone(), v.weight))
+                    .collect(),
+            }),
+            grandpa: Some(GrandpaConfig {
+                authorities: self
+                    .authorities
+                    .iter()
+                    .map(|v| (v.grandpa.clone(), v.weight))
+                    .collect(),
+            }),
+            erc20: Some(erc20_genesis(&self.tokens)),
+        })
+    }
+
+    /// Check the chain and build its chainspec. Node keys of bootnodes given by key are
+    /// generated if missing, once everything else has been checked, so an invalid chain writes no
+    /// key files.
+    pub fn build(mut self) -> Result<ChainSpec<GenesisConfig>, String> {
+        check_validators(&self.authorities)?;
+        check_tokens(&self.tokens)?;
+        check_balances(&self.balances)?;
+        check_vesting(&self.vesting, &self.balances)?;
+        self.network.check()?;
+        validate_genesis(&self.genesis()?)?;
+        self.network.resolve_bootnode_keys()?;
+        let builder = self.clone();
+        Ok(ChainSpec::from_genesis(
+            &self.name,
+            &self.id,
+            move || builder.genesis().expect("genesis was built above"),
+            self.network.bootnodes.clone(),
+            self.network.telemetry_endpoints(),
+            self.protocol_id.as_ref().map(String::as_str),
+            self.network.properties.to_properties(),
+            None,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain_spec::dev_validator;
+    use substrate_warmup_common::DevAccount;
+
+    fn alice() -> ChainSpecBuilder {
+        ChainSpecBuilder::new("Builder Testnet", "builder-testnet")
+            .authority(dev_validator(DevAccount::Alice))
+            .sudo(DevAccount::Alice.account_id())
+            .balance(DevAccount::Alice.account_id(), 1_000)
+    }
+
+    #[test]
+    fn t_build() {
+        let spec = alice()
+            .protocol_id("builder-testnet")
+            .bootnode("/ip4/127.0.0.1/tcp/30333/p2p/QmRpheLN4JWdAnY7HGJfWFNbfkQCb6tFf4vvA6hgjMZKrR")
+            .properties(ChainProperties {
+                token_symbol: Some("WARM".into()),
+                ..Default::default()
+            })
+            .build()
+            .unwrap();
+        assert_eq!(spec.name(), "Builder Testnet");
+        assert_eq!(spec.id(), "builder-testnet");
+        assert_eq!(spec.protocol_id(), Some("builder-testnet"));
+        let json: serde_json::Value = serde_json::from_str(&spec.to_json(false).unwrap()).unwrap();
+        assert_eq!(json["bootNodes"].as_array().unwrap().len(), 1);
+        assert_eq!(json["properties"]["tokenSymbol"], "WARM");
+        assert_eq!(
+            json["genesis"]["runtime"]["balances"]["balances"][0][1],
+            1_000
+        );
+    }
+
+    #[test]
+    fn t_build_bootnode_key() {
+        let file = std::env::temp_dir().join("substrate-warmup-t_build_bootnode_key");
+        let _ = std::fs::remove_file(&file);
+        let key = BootnodeKey {
+            file: file.clone(),
+            host: "10.0.0.1".into(),
+            port: 30333,
+        };
+        let spec = alice().bootnode_key(key.clone()).build().unwrap();
+        let json: serde_json::Value = serde_json::from_str(&spec.to_json(false).unwrap()).unwrap();
+        assert_eq!(json["bootNodes"][0], key.multiaddr().unwrap().as_str());
+        std::fs::remove_file(&file).unwrap();
+
+        ChainSpecBuilder::new("Builder Testnet", "builder-testnet")
+            .authority(dev_validator(DevAccount::Alice))
+            .bootnode_key(key)
+            .build()
+            .unwrap_err();
+        assert!(!file.exists());
+    }
+
+    #[test]
+    fn t_build_invalid() {
+        ChainSpecBuilder::new("Builder Testnet", "builder-testnet")
+            .authority(dev_validator(DevAccount::Alice))
+            .balance(DevAccount::Alice.account_id(), 1_000)
+            .build()
+            .unwrap_err();
+        alice()
+            .bootnode("/ip4/127.0.0.1/tcp/30333")
+            .build()
+            .unwrap_err();
+        alice()
+            .balance(DevAccount::Alice.account_id(), 1)
+            .build()
+            .unwrap_err();
+        alice()
+            .authority(dev_validator(DevAccount::Alice))
+            .build()
+            .unwrap_err();
+    }
+}
@@ -1,22 +1,25 @@
-use erc20::Erc20Token;
-use node_template_runtime::{
-    BabeConfig, BalancesConfig, Erc20Config, GenesisConfig, GrandpaConfig, SudoConfig,
-    SystemConfig, WASM_BINARY,
-};
+use crate::allocation::{add_token_allocations, read_balances, read_token_allocations};
+use crate::builder::ChainSpecBuilder;
+use crate::config::{self, ChainConfig};
+use crate::network::Network;
+use crate::token::{parse_token, TokenFile, TokenSpec};
+use crate::validator::{parse_validator, Validator};
+use crate::vesting::{parse_vesting, Vesting};
+use crate::wasm::load_wasm;
+use node_template_runtime::GenesisConfig;
 use serde::{Deserialize, Serialize};
 use sr_primitives::AccountId32;
+use std::path::PathBuf;
 use structopt::StructOpt;
 use substrate_chain_spec::ChainSpec;
 use substrate_consensus_babe_primitives::AuthorityId as BabeId;
 use substrate_finality_grandpa_primitives::AuthorityId as GrandpaId;
-use substrate_primitives::sr25519;
-use substrate_primitives::{Pair, Public};
-use substrate_warmup_common::{parse_accountid32, parse_pubkey};
+use substrate_warmup_common::{parse_accountid32, parse_pubkey, DevAccount};
 
 #[derive(StructOpt, Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Serialize, Deserialize)]
 /// generate a substrate chainspec
 pub enum Chain {
-    /// Outputs the chainspec for a shared testnet with a custom validator, root, and treasury
+    /// Outputs the chainspec for a shared testnet with custom validators, root, and treasury
     Custom {
         #[structopt(parse(try_from_str = parse_pubkey))]
         validator_grandpa: GrandpaId,
@@ -26,146 +29,289 @@ pub enum Chain {
         root_key: AccountId32,
         #[structopt(parse(try_from_str = parse_accountid32))]
         treasury: AccountId32,
+        /// Babe and grandpa weight of the validator given as positional arguments.
+        #[structopt(long, default_value = "1")]
+        validator_weight: u64,
+        /// Additional validators, formatted as `<grandpa-key>,<babe-key>[,<weight>]`. May be
+        /// repeated.
+        #[structopt(long = "validator", parse(try_from_str = parse_validator))]
+        validators: Vec<Validator>,
+        /// Erc20 tokens to mint, formatted as `<name>,<ticker>,<total-supply>,<holder>`. May be
+        /// repeated. When no tokens are given, PSTABLE1 and PSTABLE2 are minted to the treasury.
+        #[structopt(long = "token", parse(try_from_str = parse_token))]
+        tokens: Vec<TokenSpec>,
+        /// A toml or json file containing a list of erc20 tokens to mint, see
+        /// ./example-chain.toml for the format.
+        #[structopt(long, parse(from_os_str))]
+        tokens_file: Option<PathBuf>,
+        /// Csv file of `account,amount` rows, each endowing an account with native currency. May
+        /// be repeated.
+        #[structopt(long = "balances-csv", parse(from_os_str))]
+        balances_csv: Vec<PathBuf>,
+        /// Csv file of `token,account,amount` rows, each allocating part of the supply of the
+        /// token with that ticker. May be repeated.
+        #[structopt(long = "allocations-csv", parse(from_os_str))]
+        allocations_csv: Vec<PathBuf>,
+        /// Vesting schedule of an endowed account, formatted as
+        /// `<account>,<start-block>,<length-in-blocks>,<liquid-amount>`. May be repeated.
+        #[structopt(long = "vesting", parse(try_from_str = parse_vesting))]
+        vesting: Vec<Vesting>,
+        #[structopt(flatten)]
+        network: Network,
+        /// Runtime wasm to embed in the chainspec instead of the runtime chaingen was built
+        /// with. Its spec name must match.
+        #[structopt(long, parse(from_os_str))]
+        wasm: Option<PathBuf>,
     },
     /// Outputs the chainspec for a testnet with Alice as validator, root, and treasury
-    Ved,
+    Ved {
+        /// Runtime wasm to embed in the chainspec instead of the runtime chaingen was built
+        /// with. Its spec name must match.
+        #[structopt(long, parse(from_os_str))]
+        wasm: Option<PathBuf>,
+    },
+    /// Outputs the chainspec for a local testnet validated by several dev accounts, with Alice as
+    /// root and treasury and every dev account endowed
+    Local {
+        /// Number of validators, taken in order from Alice, Bob, Charlie, Dave, Eve and Ferdie.
+        #[structopt(long, default_value = "3")]
+        validators: usize,
+        /// Runtime wasm to embed in the chainspec instead of the runtime chaingen was built
+        /// with. Its spec name must match.
+        #[structopt(long, parse(from_os_str))]
+        wasm: Option<PathBuf>,
+    },
+    /// Outputs the chainspec described by a toml or json config file
+    FromConfig {
+        /// Path to the config file. Files with a .json extension are read as json, anything
+        /// else is read as toml.
+        #[structopt(parse(from_os_str))]
+        path: PathBuf,
+        /// Runtime wasm to embed in the chainspec, overriding any `wasm` set in the config file.
+        /// Its spec name must match the runtime chaingen was built with.
+        #[structopt(long, parse(from_os_str))]
+        wasm: Option<PathBuf>,
+    },
 }
 
 impl Chain {
     /// Get an actual chain config from one of the alternatives.
-    pub fn generate(self) -> ChainSpec<GenesisConfig> {
-        match self {
+    pub fn generate(self) -> Result<ChainSpec<GenesisConfig>, String> {
+        self.builder()?.build()
+    }
+
+    /// Describe the chosen chain, reading any files it refers to.
+    pub fn builder(self) -> Result<ChainSpecBuilder, String> {
+        let wasm = match &self {
+            Chain::Custom { wasm, .. }
+            | Chain::Ved { wasm }
+            | Chain::Local { wasm, .. }
+            | Chain::FromConfig { wasm, .. } => wasm.clone(),
+        };
+        let builder = match self {
             Chain::Custom {
                 validator_grandpa,
                 validator_babe,
                 root_key,
                 treasury,
+                validator_weight,
+                validators,
+                mut tokens,
+                tokens_file,
+                balances_csv,
+                allocations_csv,
+                vesting,
+                network,
+                wasm: _,
             } => {
                 let protocol_id: String = format!(
                     "substrate-warmup-custom-{}-{}-{}-{}",
                     &validator_grandpa, &validator_babe, &root_key, &treasury
                 );
-                ChainSpec::from_genesis(
+                if let Some(path) = tokens_file {
+                    tokens.extend(config::load::<TokenFile>(&path)?.tokens);
+                }
+                if tokens.is_empty() {
+                    tokens = pstable_tokens(&treasury);
+                }
+                for path in &allocations_csv {
+                    add_token_allocations(&mut tokens, read_token_allocations(path)?)?;
+                }
+                let mut builder = ChainSpecBuilder::new(
                     "Substrate Warmup Custom Testnet",
                     "substrate-warmup-custom",
-                    move || {
-                        testnet_genesis(
-                            (validator_grandpa.clone(), validator_babe.clone()),
-                            root_key.clone(),
-                            treasury.clone(),
-                        )
-                    },
-                    vec![],
-                    None,
-                    Some(&protocol_id),
-                    None,
-                    None,
                 )
+                .protocol_id(&protocol_id)
+                .authority(Validator {
+                    grandpa: validator_grandpa,
+                    babe: validator_babe,
+                    weight: validator_weight,
+                })
+                .authorities(validators)
+                .sudo(root_key)
+                .balance(treasury, ENDOWMENT)
+                .vesting(vesting)
+                .tokens(tokens)
+                .network(network);
+                for path in &balances_csv {
+                    builder = builder.balances(read_balances(path)?);
+                }
+                builder
             }
-            Chain::Ved => ChainSpec::from_genesis(
+            Chain::Ved { .. } => ChainSpecBuilder::new(
                 "Substrate Warmup Local Dev Testnet",
                 "substrate-warmup-local",
-                || {
-                    testnet_genesis(
-                        (
-                            get_from_seed::<GrandpaId>("Alice"),
-                            get_from_seed::<BabeId>("Alice"),
-                        ),
-                        id32_from_sr_seed("Alice"),
-                        id32_from_sr_seed("Alice"),
-                    )
-                },
-                vec![],
-                None,
-                None,
-                None,
-                None,
-            ),
-        }
+            )
+            .authority(dev_validator(DevAccount::Alice))
+            .sudo(DevAccount::Alice.account_id())
+            .balance(DevAccount::Alice.account_id(), ENDOWMENT)
+            .tokens(pstable_tokens(&DevAccount::Alice.account_id())),
+            Chain::Local { validators, .. } => {
+                if validators == 0 || validators > DevAccount::ALL.len() {
+                    return Err(format!(
+                        "local testnets have between 1 and {} validators",
+                        DevAccount::ALL.len()
+                    ));
+                }
+                ChainSpecBuilder::new(
+                    "Substrate Warmup Local Testnet",
+                    "substrate-warmup-local-testnet",
+                )
+                .protocol_id("substrate-warmup-local-testnet")
+                .authorities(
+                    DevAccount::ALL[..validators]
+                        .iter()
+                        .map(|account| dev_validator(*account)),
+                )
+                .sudo(DevAccount::Alice.account_id())
+                .balances(
+                    DevAccount::ALL
+                        .iter()
+                        .map(|account| (account.account_id(), ENDOWMENT)),
+                )
+                .tokens(pstable_tokens(&DevAccount::Alice.account_id()))
+            }
+            Chain::FromConfig { path, .. } => {
+                let config = ChainConfig::load(&path)?;
+                let mut builder = ChainSpecBuilder::new(&config.name, &config.id)
+                    .authorities(config.validators())
+                    .sudo(config.sudo.clone())
+                    .balances(config.endowed_accounts())
+                    .vesting(config.vesting.clone())
+                    .tokens(config.tokens.clone())
+                    .network(config.network());
+                if let Some(protocol_id) = &config.protocol_id {
+                    builder = builder.protocol_id(protocol_id);
+                }
+                // --wasm takes precedence over the config file
+                if let (Some(path), None) = (&config.wasm, &wasm) {
+                    builder = builder.wasm(load_wasm(path)?);
+                }
+                builder
+            }
+        };
+        Ok(match wasm {
+            Some(path) => builder.wasm(load_wasm(&path)?),
+            None => builder,
+        })
     }
 }
 
-fn testnet_genesis(
-    initial_authority: (GrandpaId, BabeId),
-    root_key: AccountId32,
-    treasury: AccountId32,
-) -> GenesisConfig {
-    const ENDOWMENT: u128 = u128::max_value();
-
-    GenesisConfig {
-        system: Some(SystemConfig {
-            code: WASM_BINARY.to_vec(),
-            changes_trie_config: Default::default(),
-        }),
-        balances: Some(BalancesConfig {
-            balances: vec![(treasury.clone(), ENDOWMENT)],
-            vesting: vec![],
-        }),
-        sudo: Some(SudoConfig { key: root_key }),
-        babe: Some(BabeConfig {
-            authorities: vec![(initial_authority.1, 1)],
-        }),
-        grandpa: Some(GrandpaConfig {
-            authorities: vec![(initial_authority.0, 1)],
-        }),
-        erc20: Some(Erc20Config {
-            initial_tokens: vec![
-                (
-                    Erc20Token {
-                        name: b"PSTABLE1".to_vec(),
-                        ticker: b"PSTABLE1".to_vec(),
-                        total_supply: ENDOWMENT,
-                    },
-                    treasury.clone(),
-                ),
-                (
-                    Erc20Token {
-                        name: b"PSTABLE2".to_vec(),
-                        ticker: b"PSTABLE2".to_vec(),
-                        total_supply: ENDOWMENT,
-                    },
-                    treasury.clone(),
-                ),
-            ],
-        }),
-    }
-}
+/// Balance given to the treasury of the builtin chains, both in native currency and in each
+/// PSTABLE token. Far below `validate::MAX_SUPPLY`, so csv balances can be added on top.
+const ENDOWMENT: u128 = 1_000_000_000_000_000_000_000_000;
 
-/// Helper function to generate a crypto pair from seed
-pub fn get_from_seed<P: Public>(seed: &str) -> <P::Pair as Pair>::Public {
-    P::Pair::from_string(&format!("//{}", seed), None)
-        .expect("invalid seed")
-        .public()
+/// The PSTABLE1 and PSTABLE2 tokens of the builtin chains, entire supply held by treasury.
+fn pstable_tokens(treasury: &AccountId32) -> Vec<TokenSpec> {
+    ["PSTABLE1", "PSTABLE2"]
+        .iter()
+        .map(|name| TokenSpec {
+            name: name.to_string(),
+            ticker: name.to_string(),
+            total_supply: ENDOWMENT,
+            holder: treasury.clone(),
+            allocations: vec![],
+        })
+        .collect()
 }
 
-/// Derive sr25519 key from private key seed, return corresponding publik key as
-/// an AccountId32.
-fn id32_from_sr_seed(seed: &str) -> AccountId32 {
-    AccountId32::from(get_from_seed::<sr25519::Public>(seed).0)
+/// Babe and grandpa keys of a dev account, with a weight of 1.
+pub fn dev_validator(account: DevAccount) -> Validator {
+    Validator::new(account.grandpa_id(), account.babe_id())
 }
 
 #[cfg(test)]
-mod tests {
+pub(crate) mod tests {
     use super::*;
-    use node_template_runtime::Runtime;
+    use node_template_runtime::{Runtime, WASM_BINARY};
+    use sr_primitives::BuildStorage;
+    use substrate_primitives::sr25519;
+
+    pub(crate) const VALID_PK: &str =
+        "0x6e4e511be3eae0696f542e7c05f99e5f5e7b19ce311fc8ef7c2139e0505c305c";
+
+    /// A custom chain whose validator, root and treasury are all `VALID_PK`, with nothing else
+    /// set. Tests override the fields they care about.
+    pub(crate) fn custom() -> Chain {
+        Chain::Custom {
+            validator_grandpa: parse_pubkey(VALID_PK).unwrap(),
+            validator_babe: parse_pubkey(VALID_PK).unwrap(),
+            root_key: parse_accountid32(VALID_PK).unwrap(),
+            treasury: parse_accountid32(VALID_PK).unwrap(),
+            validator_weight: 1,
+            validators: vec![],
+            tokens: vec![],
+            tokens_file: None,
+            balances_csv: vec![],
+            allocations_csv: vec![],
+            vesting: vec![],
+            network: Default::default(),
+            wasm: None,
+        }
+    }
+
+    /// Write `contents` to a file in the temp dir, named after the test using it.
+    pub(crate) fn temp_file(name: &str, contents: impl AsRef<[u8]>) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("substrate-warmup-{}", name));
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
 
     #[test]
     fn t_parse_pk() {
-        let valid_pk = "0x6e4e511be3eae0696f542e7c05f99e5f5e7b19ce311fc8ef7c2139e0505c305c";
-        parse_pubkey::<GrandpaId>(valid_pk).unwrap();
-        parse_pubkey::<BabeId>(valid_pk).unwrap();
-        parse_pubkey::<sr25519::Public>(valid_pk).unwrap();
-        parse_accountid32(valid_pk).unwrap();
+        parse_pubkey::<GrandpaId>(VALID_PK).unwrap();
+        parse_pubkey::<BabeId>(VALID_PK).unwrap();
+        parse_pubkey::<sr25519::Public>(VALID_PK).unwrap();
+        parse_accountid32(VALID_PK).unwrap();
+
+        // ss58, secret uris and dev account names are accepted too
+        assert_eq!(
+            parse_accountid32("5EZLPYKPLdfHutUAxx7hYVqwxmtjcw6MrtNygajayUDQzoSM").unwrap(),
+            parse_accountid32(VALID_PK).unwrap()
+        );
+        assert_eq!(
+            parse_accountid32("//Alice").unwrap(),
+            DevAccount::Alice.account_id()
+        );
+        assert_eq!(
+            parse_accountid32("alice").unwrap(),
+            DevAccount::Alice.account_id()
+        );
+        assert_eq!(
+            parse_pubkey::<GrandpaId>("//Alice").unwrap(),
+            DevAccount::Alice.grandpa_id()
+        );
+        assert_eq!(
+            parse_pubkey::<BabeId>("//Alice").unwrap(),
+            DevAccount::Alice.babe_id()
+        );
 
         for invalid_pk in &[
             "0x6e4e511be3eae0696f542e7c05f99e5f5e7b19ce311fc8ef7c2139e0505c305",
             "6e4e511be3eae0696f542e7c05f99e5f5e7b19ce311fc8ef7c2139e0505c305c",
             " 0x6e4e511be3eae0696f542e7c05f99e5f5e7b19ce311fc8ef7c2139e0505c305c",
-            "5EZLPYKPLdfHutUAxx7hYVqwxmtjcw6MrtNygajayUDQzoSM",
-            "//Alice",
-            "/Alice",
-            "Alice",
-            "wet comic voice screen voyage hobby target prevent cluster moral menu mammal",
+            "5EZLPYKPLdfHutUAxx7hYVqwxmtjcw6MrtNygajayUDQzoSN",
+            "Alicia",
         ] {
             parse_pubkey::<GrandpaId>(invalid_pk).unwrap_err();
             parse_pubkey::<BabeId>(invalid_pk).unwrap_err();
@@ -177,33 +323,46 @@ mod tests {
     #[test]
     // this test takes several seconds, may be worth optimizing or removing
     fn t_generate() {
-        let valid_pk = "0x6e4e511be3eae0696f542e7c05f99e5f5e7b19ce311fc8ef7c2139e0505c305c";
-
         for chain in &[
-            Chain::Custom {
-                validator_grandpa: parse_pubkey::<GrandpaId>(valid_pk).unwrap(),
-                validator_babe: parse_pubkey::<BabeId>(valid_pk).unwrap(),
-                root_key: parse_accountid32(valid_pk).unwrap(),
-                treasury: parse_accountid32(valid_pk).unwrap(),
+            custom(),
+            Chain::Ved { wasm: None },
+            Chain::Local {
+                validators: 3,
+                wasm: None,
             },
-            Chain::Ved,
         ] {
-            chain.clone().generate().to_json(true).unwrap();
-            chain.clone().generate().to_json(false).unwrap();
+            chain.clone().generate().unwrap().to_json(true).unwrap();
+            chain.clone().generate().unwrap().to_json(false).unwrap();
         }
     }
 
     #[test]
-    fn t_generate_protocol_id() {
-        let valid_pk = "0x6e4e511be3eae0696f542e7c05f99e5f5e7b19ce311fc8ef7c2139e0505c305c";
+    fn t_generate_wasm() {
+        let path = temp_file("t_generate_wasm.wasm", WASM_BINARY);
+        let genesis = Chain::Ved {
+            wasm: Some(path.clone()),
+        }
+        .builder()
+        .unwrap()
+        .genesis()
+        .unwrap();
+        assert_eq!(
+            genesis.system.unwrap().code,
+            node_template_runtime::WASM_BINARY
+        );
 
-        let genesis = Chain::Custom {
-            validator_grandpa: parse_pubkey::<GrandpaId>(valid_pk).unwrap(),
-            validator_babe: parse_pubkey::<BabeId>(valid_pk).unwrap(),
-            root_key: parse_accountid32(valid_pk).unwrap(),
-            treasury: parse_accountid32(valid_pk).unwrap(),
+        std::fs::write(&path, b"not wasm").unwrap();
+        Chain::Ved {
+            wasm: Some(path.clone()),
         }
-        .generate();
+        .generate()
+        .unwrap_err();
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn t_generate_protocol_id() {
+        let genesis = custom().generate().unwrap();
         let prot_id = genesis.protocol_id().unwrap();
         assert_eq!(
             prot_id,
@@ -215,6 +374,255 @@ mod tests {
         );
     }
 
+    #[test]
+    fn t_invalid_bootnode() {
+        let mut chain = custom();
+        if let Chain::Custom { network, .. } = &mut chain {
+            network.bootnodes = vec!["/ip4/127.0.0.1/tcp/30333".into()];
+        }
+        let err = chain.generate().unwrap_err();
+        assert!(err.contains("invalid bootnode"));
+    }
+
+    #[test]
+    fn t_from_config() {
+        let path = PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/example-chain.toml"));
+        let spec = Chain::FromConfig { path, wasm: None }.generate().unwrap();
+        assert_eq!(spec.name(), "Substrate Warmup Example Testnet");
+        assert_eq!(spec.id(), "substrate-warmup-example");
+        assert_eq!(spec.protocol_id(), Some("substrate-warmup-example"));
+        spec.to_json(true).unwrap();
+
+        let json: serde_json::Value = serde_json::from_str(&spec.to_json(false).unwrap()).unwrap();
+        assert_eq!(
+            json["bootNodes"],
+            serde_json::json!([
+                "/ip4/127.0.0.1/tcp/30333/p2p/QmRpheLN4JWdAnY7HGJfWFNbfkQCb6tFf4vvA6hgjMZKrR"
+            ])
+        );
+        assert_eq!(
+            json["properties"],
+            serde_json::json!({ "tokenSymbol": "WARM", "tokenDecimals": 12, "ss58Format": 42 })
+        );
+        assert!(!json["telemetryEndpoints"].is_null());
+    }
+
+    #[test]
+    fn t_from_config_csv() {
+        let path = PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/example-chain.toml"));
+        let config = ChainConfig::load(&path).unwrap();
+        let endowed: Vec<AccountId32> = config
+            .endowed_accounts()
+            .into_iter()
+            .map(|(account, _)| account)
+            .collect();
+        assert!(endowed.contains(&DevAccount::Charlie.account_id()));
+        assert!(endowed.contains(&DevAccount::Dave.account_id()));
+        let allocated: Vec<&AccountId32> = config.tokens[0]
+            .allocations
+            .iter()
+            .map(|a| &a.account)
+            .collect();
+        assert_eq!(
+            allocated,
+            vec![
+                &DevAccount::Bob.account_id(),
+                &DevAccount::Charlie.account_id(),
+                &DevAccount::Dave.account_id()
+            ]
+        );
+    }
+
+    #[test]
+    fn t_from_config_missing() {
+        Chain::FromConfig {
+            path: PathBuf::from("this/file/does/not/exist.toml"),
+            wasm: None,
+        }
+        .generate()
+        .unwrap_err();
+    }
+
+    #[test]
+    fn t_from_config_wasm_override() {
+        let dir = std::env::temp_dir().join("substrate-warmup-t_from_config_wasm_override");
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("chain.toml");
+        std::fs::write(
+            &path,
+            r#"
+                name = "Wasm Testnet"
+                id = "wasm-testnet"
+                sudo = "alice"
+                wasm = "missing.wasm"
+
+                [[authorities]]
+                grandpa = "alice"
+                babe = "alice"
+
+                [[balances]]
+                account = "alice"
+                amount = 1000000
+            "#,
+        )
+        .unwrap();
+        let wasm = dir.join("runtime.wasm");
+        std::fs::write(&wasm, WASM_BINARY).unwrap();
+
+        // the config's wasm does not exist, so it is only read when --wasm is not given
+        let err = Chain::FromConfig {
+            path: path.clone(),
+            wasm: None,
+        }
+        .generate()
+        .unwrap_err();
+        assert!(err.contains("missing.wasm"), "{}", err);
+        let genesis = Chain::FromConfig {
+            path,
+            wasm: Some(wasm),
+        }
+        .builder()
+        .unwrap()
+        .genesis()
+        .unwrap();
+        assert_eq!(genesis.system.unwrap().code, WASM_BINARY);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn t_weighted_validators() {
+        let a = VALID_PK;
+        let b = "0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d";
+        let with_validators = |weight, extra_validators| {
+            let mut chain = custom();
+            if let Chain::Custom {
+                validator_weight,
+                validators,
+                ..
+            } = &mut chain
+            {
+                *validator_weight = weight;
+                *validators = extra_validators;
+            }
+            chain
+        };
+
+        let spec = with_validators(2, vec![parse_validator(&format!("{},{},3", b, b)).unwrap()])
+            .generate()
+            .unwrap();
+        let genesis = spec.to_json(false).unwrap();
+        let genesis: serde_json::Value = serde_json::from_str(&genesis).unwrap();
+        let babe = &genesis["genesis"]["runtime"]["babe"]["authorities"];
+        assert_eq!(babe.as_array().unwrap().len(), 2);
+        assert_eq!(babe[0][1], 2);
+        assert_eq!(babe[1][1], 3);
+        let grandpa = &genesis["genesis"]["runtime"]["grandpa"]["authorities"];
+        assert_eq!(grandpa.as_array().unwrap().len(), 2);
+        assert_eq!(grandpa[0][1], 2);
+        assert_eq!(grandpa[1][1], 3);
+
+        // invalid sets are rejected, see validator::tests for which
+        with_validators(0, vec![]).generate().unwrap_err();
+        with_validators(1, vec![parse_validator(&format!("{},{}", a, b)).unwrap()])
+            .generate()
+            .unwrap_err();
+    }
+
+    #[test]
+    fn t_tokens() {
+        let mut chain = custom();
+        if let Chain::Custom { tokens, .. } = &mut chain {
+            *tokens = vec![parse_token(&format!("Dock Dollar,DD,100,{}", VALID_PK)).unwrap()];
+        }
+        let spec = chain.generate().unwrap();
+        let genesis: serde_json::Value =
+            serde_json::from_str(&spec.to_json(false).unwrap()).unwrap();
+        let tokens = &genesis["genesis"]["runtime"]["erc20"]["initialTokens"];
+        assert_eq!(tokens.as_array().unwrap().len(), 1);
+        assert_eq!(tokens[0][0]["total_supply"], 100);
+
+        // invalid tokens are rejected, see token::tests for which
+        if let Chain::Custom { tokens, .. } = &mut chain {
+            tokens[0].name = "n".repeat(erc20::MAX_NAME_LEN + 1);
+        }
+        chain.generate().unwrap_err();
+    }
+
+    #[test]
+    fn t_vesting() {
+        let treasury = parse_accountid32(VALID_PK).unwrap();
+        let bob = DevAccount::Bob.account_id();
+        let with_vesting = |schedules| {
+            let mut chain = custom();
+            if let Chain::Custom { vesting, .. } = &mut chain {
+                *vesting = schedules;
+            }
+            chain
+        };
+        let schedule = |account: &AccountId32, liquid, length| Vesting {
+            account: account.clone(),
+            start: 0,
+            length,
+            liquid,
+        };
+
+        with_vesting(vec![schedule(&treasury, 1000, 100)])
+            .generate()
+            .unwrap()
+            .build_storage()
+            .unwrap();
+        with_vesting(vec![schedule(&treasury, ENDOWMENT, 100)])
+            .generate()
+            .unwrap();
+
+        // invalid schedules are rejected, see vesting::tests for which
+        with_vesting(vec![schedule(&bob, 0, 100)])
+            .generate()
+            .unwrap_err();
+    }
+
+    #[test]
+    fn t_local() {
+        let spec = Chain::Local {
+            validators: 3,
+            wasm: None,
+        }
+        .generate()
+        .unwrap();
+        assert_eq!(spec.protocol_id(), Some("substrate-warmup-local-testnet"));
+        let genesis: serde_json::Value =
+            serde_json::from_str(&spec.to_json(false).unwrap()).unwrap();
+        let runtime = &genesis["genesis"]["runtime"];
+        let babe = runtime["babe"]["authorities"].as_array().unwrap();
+        assert_eq!(babe.len(), 3);
+        assert_eq!(babe[1][0], DevAccount::Bob.babe_id().to_string().as_str());
+        let grandpa = runtime["grandpa"]["authorities"].as_array().unwrap();
+        assert_eq!(grandpa.len(), 3);
+        assert_eq!(
+            runtime["balances"]["balances"].as_array().unwrap().len(),
+            DevAccount::ALL.len()
+        );
+
+        Chain::Local {
+            validators: 6,
+            wasm: None,
+        }
+        .generate()
+        .unwrap();
+        Chain::Local {
+            validators: 0,
+            wasm: None,
+        }
+        .generate()
+        .unwrap_err();
+        Chain::Local {
+            validators: 7,
+            wasm: None,
+        }
+        .generate()
+        .unwrap_err();
+    }
+
     #[test]
     fn account_id_is_system_account_id() {
         use std::any::TypeId;
@@ -0,0 +1,156 @@
+//! Declarative chain descriptions. A `ChainConfig` is read from a toml or json file and holds
+//! everything needed to generate a chainspec without touching the code.
+//!
+//! See ./example-chain.toml for an annotated example.
+
+use crate::allocation::{add_token_allocations, read_balances, read_token_allocations};
+use crate::network::{ChainProperties, Network, Telemetry};
+use crate::node_key::BootnodeKey;
+use crate::token::TokenSpec;
+use crate::validator::Validator;
+use crate::vesting::Vesting;
+use serde::{de, de::DeserializeOwned, Deserialize, Deserializer};
+use sr_primitives::AccountId32;
+use std::path::{Path, PathBuf};
+use substrate_primitives::Public;
+use substrate_warmup_common::{parse_accountid32, parse_pubkey};
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct ChainConfig {
+    /// Human readable name of the chain.
+    pub name: String,
+    /// Machine readable chain id.
+    pub id: String,
+    /// Network protocol id, used to keep peers of different chains apart.
+    pub protocol_id: Option<String>,
+    /// Initial validator set.
+    pub authorities: Vec<Validator>,
+    /// Superuser account.
+    #[serde(deserialize_with = "account")]
+    pub sudo: AccountId32,
+    /// Initial native token balances.
+    #[serde(default)]
+    pub balances: Vec<BalanceConfig>,
+    /// Erc20 tokens minted at genesis.
+    #[serde(default)]
+    pub tokens: Vec<TokenSpec>,
+    /// Vesting schedules of endowed accounts.
+    #[serde(default)]
+    pub vesting: Vec<Vesting>,
+    /// Csv files of `account,amount` rows, added to `balances` on load. Relative paths are
+    /// relative to the config file.
+    #[serde(default)]
+    pub balances_csv: Vec<PathBuf>,
+    /// Csv files of `token,account,amount` rows, added to the allocations of `tokens` on load.
+    /// Relative paths are relative to the config file.
+    #[serde(default)]
+    pub allocations_csv: Vec<PathBuf>,
+    /// Multiaddrs of nodes new nodes should connect to.
+    #[serde(default)]
+    pub bootnodes: Vec<String>,
+    /// Bootnodes to generate node keys for, their multiaddrs are added to `bootnodes`. Key files
+    /// are relative to the config file.
+    #[serde(default)]
+    pub bootnode_keys: Vec<BootnodeKey>,
+    /// Telemetry endpoints and their verbosity.
+    #[serde(default)]
+    pub telemetry: Vec<Telemetry>,
+    /// Token symbol, token decimals and ss58 format.
+    #[serde(default)]
+    pub properties: ChainProperties,
+    /// Runtime wasm to embed instead of the runtime chaingen was built with. Relative to the
+    /// config file.
+    pub wasm: Option<PathBuf>,
+}
+
+#[derive(Deserialize, Clone, Debug)]
+#[serde(deny_unknown_fields)]
+pub struct BalanceConfig {
+    #[serde(deserialize_with = "account")]
+    pub account: AccountId32,
+    #[serde(deserialize_with = "amount")]
+    pub amount: u128,
+}
+
+impl ChainConfig {
+    /// Read a chain description from a file, along with any csv files it refers to.
+    pub fn load(path: &Path) -> Result<Self, String> {
+        let mut config: Self = load(path)?;
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+        for csv in &config.balances_csv {
+            let balances = read_balances(&dir.join(csv))?;
+            config.balances.extend(
+                balances
+                    .into_iter()
+                    .map(|(account, amount)| BalanceConfig { account, amount }),
+            );
+        }
+        for csv in &config.allocations_csv {
+            add_token_allocations(&mut config.tokens, read_token_allocations(&dir.join(csv))?)?;
+        }
+        for key in &mut config.bootnode_keys {
+            key.file = dir.join(&key.file);
+        }
+        config.wasm = config.wasm.map(|wasm| dir.join(wasm));
+        Ok(config)
+    }
+
+    pub fn validators(&self) -> Vec<Validator> {
+        self.authorities.clone()
+    }
+
+    pub fn network(&self) -> Network {
+        Network {
+            bootnodes: self.bootnodes.clone(),
+            bootnode_keys: self.bootnode_keys.clone(),
+            telemetry: self.telemetry.clone(),
+            properties: self.properties.clone(),
+        }
+    }
+
+    pub fn endowed_accounts(&self) -> Vec<(AccountId32, u128)> {
+        self.balances
+            .iter()
+            .map(|b| (b.account.clone(), b.amount))
+            .collect()
+    }
+}
+
+/// Read a file into T. Files ending in `.json` are parsed as json, everything else is parsed
+/// as toml.
+pub fn load<T: DeserializeOwned>(path: &Path) -> Result<T, String> {
+    let contents = std::fs::read_to_string(path)
+        .map_err(|e| format!("could not read {}: {}", path.display(), e))?;
+    let parsed = if path.extension().map_or(false, |ext| ext == "json") {
+        serde_json::from_str(&contents).map_err(|e| e.to_string())
+    } else {
+        toml::from_str(&contents).map_err(|e| e.to_string())
+    };
+    parsed.map_err(|e| format!("invalid config {}: {}", path.display(), e))
+}
+
+pub fn account<'de, D: Deserializer<'de>>(deserializer: D) -> Result<AccountId32, D::Error> {
+    let s = String::deserialize(deserializer)?;
+    parse_accountid32(&s).map_err(de::Error::custom)
+}
+
+pub fn public<'de, D: Deserializer<'de>, T: Public>(deserializer: D) -> Result<T, D::Error> {
+    let s = String::deserialize(deserializer)?;
+    parse_pubkey(&s).map_err(de::Error::custom)
+}
+
+/// Toml integers are limited to i64 so large amounts may be written as decimal strings.
+pub fn amount<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u128, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Amount {
+        Int(u64),
+        Str(String),
+    }
+
+    match Amount::deserialize(deserializer)? {
+        Amount::Int(n) => Ok(n.into()),
+        Amount::Str(s) => s.parse().map_err(de::Error::custom),
+    }
+}
@@ -0,0 +1,558 @@
+//! Decoding of raw genesis storage back into typed module state.
+//!
+//! Storage keys are hashes, so they can't be reversed. Instead, the keys of known storage items
+//! are computed for every candidate map key and matched against the keys present in storage.
+//! Candidate accounts are the dev accounts, the sudo key, the babe authorities, the accounts named
+//! by the runtime genesis of non-raw chainspecs, and any accounts supplied by the caller.
+
+use erc20::Erc20Token;
+use node_template_runtime::{GenesisConfig, Runtime};
+use parity_scale_codec::{Decode, DecodeAll, FullCodec, FullEncode};
+use serde::{Deserialize, Serialize};
+use serde_json::value::RawValue;
+use sr_primitives::{AccountId32, BuildStorage};
+use srml_balances::VestingSchedule;
+use srml_support::storage::generator::{StorageMap, StorageValue};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use substrate_chain_spec::ChainSpec;
+use substrate_consensus_babe_primitives::AuthorityId as BabeId;
+use substrate_finality_grandpa_primitives::AuthorityId as GrandpaId;
+use substrate_primitives::{blake2_256, twox_128, H256};
+use substrate_warmup_common::{slice_to_arr32, sudo_key_raw_key, DevAccount};
+
+/// Raw key-value pairs of the top level storage trie.
+pub type Storage = HashMap<Vec<u8>, Vec<u8>>;
+
+/// Json representation of a storage value. Stored pre-serialized because serde_json::Value
+/// can't hold u128.
+pub type Json = Box<RawValue>;
+
+/// Genesis storage of a chainspec file. Works for both raw and non-raw chainspecs.
+pub fn genesis_storage(path: PathBuf) -> Result<Storage, String> {
+    let spec = ChainSpec::<GenesisConfig>::from_json_file(path)?;
+    let (top, _children) = spec.build_storage()?;
+    Ok(top)
+}
+
+/// Accounts named by the runtime genesis of a chainspec file: the sudo key, endowed and vesting
+/// accounts, and erc20 token holders and allocations. Raw chainspecs only hold hashed storage
+/// keys, so no accounts are found in them.
+pub fn genesis_accounts(path: &Path) -> Result<Vec<AccountId32>, String> {
+    #[derive(Deserialize)]
+    struct Spec {
+        genesis: SpecGenesis,
+    }
+    #[derive(Deserialize)]
+    struct SpecGenesis {
+        runtime: Option<GenesisConfig>,
+    }
+
+    let file = std::fs::File::open(path)
+        .map_err(|e| format!("could not read {}: {}", path.display(), e))?;
+    let spec: Spec = serde_json::from_reader(std::io::BufReader::new(file))
+        .map_err(|e| format!("{}: {}", path.display(), e))?;
+    Ok(spec
+        .genesis
+        .runtime
+        .map(|genesis| named_accounts(&genesis))
+        .unwrap_or_default())
+}
+
+fn named_accounts(genesis: &GenesisConfig) -> Vec<AccountId32> {
+    let mut accounts = vec![];
+    if let Some(sudo) = &genesis.sudo {
+        accounts.push(sudo.key.clone());
+    }
+    if let Some(balances) = &genesis.balances {
+        accounts.extend(balances.balances.iter().map(|(account, _)| account.clone()));
+        accounts.extend(balances.vesting.iter().map(|(account, ..)| account.clone()));
+    }
+    if let Some(erc20) = &genesis.erc20 {
+        accounts.extend(
+            erc20
+                .initial_tokens
+                .iter()
+                .map(|(_, holder)| holder.clone()),
+        );
+        accounts.extend(
+            erc20
+                .initial_allocations
+                .iter()
+                .map(|(_, account, _)| account.clone()),
+        );
+    }
+    accounts
+}
+
+/// A storage item of the runtime for which the raw key is known.
+pub struct KnownKey {
+    pub module: &'static str,
+    pub item: &'static str,
+    /// Map key, formatted for display. None for storage values.
+    pub key: Option<String>,
+    decoder: fn(&[u8]) -> Result<Json, String>,
+}
+
+impl KnownKey {
+    pub fn decode(&self, raw: &[u8]) -> Result<Json, String> {
+        (self.decoder)(raw)
+    }
+}
+
+/// Known raw storage keys, mapped to the items they represent.
+#[derive(Default)]
+pub struct KnownKeys(BTreeMap<Vec<u8>, KnownKey>);
+
+impl KnownKeys {
+    /// Compute the known keys for one or more storages. Candidate accounts and token ids are
+    /// collected from all of them.
+    pub fn new(storages: &[&Storage], accounts: &[AccountId32]) -> Self {
+        let mut ret = Self::default();
+
+        ret.insert(b":code".to_vec(), "System", "Code", None, code_summary);
+        ret.insert(
+            b":heappages".to_vec(),
+            "System",
+            "HeapPages",
+            None,
+            json::<u64>,
+        );
+        ret.insert(
+            b":extrinsic_index".to_vec(),
+            "System",
+            "ExtrinsicIndex",
+            None,
+            json::<u32>,
+        );
+        ret.insert(
+            b":grandpa_authorities".to_vec(),
+            "Grandpa",
+            "Authorities",
+            None,
+            grandpa_authorities,
+        );
+        let sudo_key = sudo_key_raw_key();
+        ret.insert(sudo_key.clone(), "Sudo", "Key", None, json::<AccountId32>);
+        ret.insert(
+            value::<srml_babe::Authorities, Vec<(BabeId, u64)>>(),
+            "Babe",
+            "Authorities",
+            None,
+            json::<Vec<(BabeId, u64)>>,
+        );
+        ret.insert(
+            value::<srml_balances::TotalIssuance<Runtime>, u128>(),
+            "Balances",
+            "TotalIssuance",
+            None,
+            json::<u128>,
+        );
+        ret.insert(
+            value::<erc20::TokenId, u32>(),
+            "Erc20",
+            "TokenId",
+            None,
+            json::<u32>,
+        );
+        ret.insert(
+            value_key("System ParentHash"),
+            "System",
+            "ParentHash",
+            None,
+            json::<H256>,
+        );
+        ret.insert(
+            value_key("System Number"),
+            "System",
+            "Number",
+            None,
+            json::<u32>,
+        );
+        ret.insert(
+            map::<srml_system::BlockHash<Runtime>, _, H256>(&0u32),
+            "System",
+            "BlockHash",
+            Some(label(&0u32)),
+            json::<H256>,
+        );
+
+        let mut candidates: BTreeSet<AccountId32> = accounts.iter().cloned().collect();
+        candidates.extend(DevAccount::ALL.iter().map(|account| account.account_id()));
+        let mut token_count: u32 = 0;
+        for storage in storages {
+            if let Some(sudo) = read::<AccountId32>(storage, &sudo_key) {
+                candidates.insert(sudo);
+            }
+            if let Some(babe) = read::<Vec<(BabeId, u64)>>(
+                storage,
+                &value::<srml_babe::Authorities, Vec<(BabeId, u64)>>(),
+            ) {
+                candidates.extend(
+                    babe.iter()
+                        .map(|(id, _)| AccountId32::from(slice_to_arr32(id.as_ref()))),
+                );
+            }
+            if let Some(count) = read::<u32>(storage, &value::<erc20::TokenId, u32>()) {
+                token_count = token_count.max(count);
+            }
+        }
+
+        for account in &candidates {
+            ret.insert(
+                map::<srml_balances::FreeBalance<Runtime>, _, u128>(account),
+                "Balances",
+                "FreeBalance",
+                Some(label(account)),
+                json::<u128>,
+            );
+            ret.insert(
+                map::<srml_balances::ReservedBalance<Runtime>, _, u128>(account),
+                "Balances",
+                "ReservedBalance",
+                Some(label(account)),
+                json::<u128>,
+            );
+            ret.insert(
+                map::<srml_balances::Vesting<Runtime>, _, VestingSchedule<u128, u32>>(account),
+                "Balances",
+                "Vesting",
+                Some(label(account)),
+                vesting_schedule,
+            );
+            ret.insert(
+                map::<srml_system::AccountNonce<Runtime>, _, u32>(account),
+                "System",
+                "AccountNonce",
+                Some(label(account)),
+                json::<u32>,
+            );
+        }
+        for token_id in 0..token_count {
+            ret.insert(
+                map::<erc20::Tokens<Runtime>, _, Erc20Token<u128>>(&token_id),
+                "Erc20",
+                "Tokens",
+                Some(label(&token_id)),
+                token_details,
+            );
+            for account in &candidates {
+                let key = (token_id, account.clone());
+                ret.insert(
+                    map::<erc20::BalanceOf<Runtime>, _, u128>(&key),
+                    "Erc20",
+                    "BalanceOf",
+                    Some(label(&key)),
+                    json::<u128>,
+                );
+            }
+        }
+
+        ret
+    }
+
+    pub fn get(&self, raw_key: &[u8]) -> Option<&KnownKey> {
+        self.0.get(raw_key)
+    }
+
+    fn insert(
+        &mut self,
+        raw_key: Vec<u8>,
+        module: &'static str,
+        item: &'static str,
+        key: Option<String>,
+        decoder: fn(&[u8]) -> Result<Json, String>,
+    ) {
+        self.0.insert(
+            raw_key,
+            KnownKey {
+                module,
+                item,
+                key,
+                decoder,
+            },
+        );
+    }
+}
+
+fn read<T: Decode>(storage: &Storage, raw_key: &[u8]) -> Option<T> {
+    storage.get(raw_key).and_then(|raw| T::decode_all(raw).ok())
+}
+
+/// Decoded storage, grouped by module then by storage item.
+#[derive(Serialize, Default)]
+pub struct Decoded {
+    #[serde(flatten)]
+    pub modules: BTreeMap<&'static str, BTreeMap<&'static str, Item>>,
+    /// Entries which could not be decoded, as hex.
+    pub unknown: BTreeMap<String, String>,
+}
+
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum Item {
+    Value(Json),
+    Map(BTreeMap<String, Json>),
+}
+
+impl Decoded {
+    pub fn new(storage: &Storage, known: &KnownKeys) -> Self {
+        let mut ret = Self::default();
+        for (raw_key, raw_value) in storage {
+            match known.get(raw_key).map(|k| (k, k.decode(raw_value))) {
+                Some((k, Ok(value))) => ret.insert(k, value),
+                _ => {
+                    ret.unknown.insert(hex(raw_key), hex(raw_value));
+                }
+            }
+        }
+        ret
+    }
+
+    fn insert(&mut self, known: &KnownKey, value: Json) {
+        let items = self.modules.entry(known.module).or_default();
+        match &known.key {
+            None => {
+                items.insert(known.item, Item::Value(value));
+            }
+            Some(key) => {
+                let entry = items
+                    .entry(known.item)
+                    .or_insert_with(|| Item::Map(BTreeMap::new()));
+                if let Item::Map(map) = entry {
+                    map.insert(key.clone(), value);
+                }
+            }
+        }
+    }
+}
+
+/// Decode the genesis storage of the chainspec at `path` into human readable json.
+pub fn decode(path: PathBuf, accounts: &[AccountId32]) -> Result<String, String> {
+    let mut accounts = accounts.to_vec();
+    accounts.extend(genesis_accounts(&path)?);
+    let storage = genesis_storage(path)?;
+    let known = KnownKeys::new(&[&storage], &accounts);
+    serde_json::to_string_pretty(&Decoded::new(&storage, &known)).map_err(|e| e.to_string())
+}
+
+/// Key of a `StorageValue` which isn't publicly exported by its module, twox128 of the prefix.
+fn value_key(prefix: &str) -> Vec<u8> {
+    twox_128(prefix.as_bytes()).to_vec()
+}
+
+fn value<S: StorageValue<V>, V: FullCodec>() -> Vec<u8> {
+    S::storage_value_final_key().to_vec()
+}
+
+fn map<S: StorageMap<K, V>, K: FullEncode, V: FullCodec>(key: &K) -> Vec<u8> {
+    S::storage_map_final_key(key).as_ref().to_vec()
+}
+
+/// Display map keys which serialize to json strings, like accounts, without quotes.
+fn label<K: Serialize>(key: &K) -> String {
+    match serde_json::to_value(key) {
+        Ok(serde_json::Value::String(s)) => s,
+        _ => serde_json::to_string(key).expect("in-memory serialization failed"),
+    }
+}
+
+pub fn hex(raw: &[u8]) -> String {
+    format!("0x{}", hex::encode(raw))
+}
+
+pub fn to_raw_json<T: Serialize>(value: &T) -> Json {
+    let s = serde_json::to_string(value).expect("in-memory serialization failed");
+    RawValue::from_string(s).expect("serde_json produced invalid json")
+}
+
+fn json<T: DecodeAll + Serialize>(raw: &[u8]) -> Result<Json, String> {
+    T::decode_all(raw)
+        .map(|value| to_raw_json(&value))
+        .map_err(|e| e.what().to_string())
+}
+
+/// The wasm blob is too large to print, show its size and hash instead.
+pub fn code_summary(raw: &[u8]) -> Result<Json, String> {
+    #[derive(Serialize)]
+    struct CodeSummary {
+        bytes: usize,
+        blake2_256: H256,
+    }
+
+    Ok(to_raw_json(&CodeSummary {
+        bytes: raw.len(),
+        blake2_256: blake2_256(raw).into(),
+    }))
+}
+
+/// Token names and tickers are shown as text rather than byte arrays.
+fn token_details(raw: &[u8]) -> Result<Json, String> {
+    #[derive(Serialize)]
+    struct TokenDetails {
+        name: String,
+        ticker: String,
+        total_supply: u128,
+    }
+
+    let token = Erc20Token::<u128>::decode_all(raw).map_err(|e| e.what().to_string())?;
+    Ok(to_raw_json(&TokenDetails {
+        name: String::from_utf8_lossy(&token.name).into_owned(),
+        ticker: String::from_utf8_lossy(&token.ticker).into_owned(),
+        total_supply: token.total_supply,
+    }))
+}
+
+/// Vesting schedules don't implement serde, show their fields instead.
+fn vesting_schedule(raw: &[u8]) -> Result<Json, String> {
+    #[derive(Serialize)]
+    struct Schedule {
+        locked: u128,
+        per_block: u128,
+        starting_block: u32,
+    }
+
+    let schedule =
+        VestingSchedule::<u128, u32>::decode_all(raw).map_err(|e| e.what().to_string())?;
+    Ok(to_raw_json(&Schedule {
+        locked: schedule.locked,
+        per_block: schedule.per_block,
+        starting_block: schedule.starting_block,
+    }))
+}
+
+/// Grandpa authorities are stored under a well known key, prefixed with a version byte.
+fn grandpa_authorities(raw: &[u8]) -> Result<Json, String> {
+    <(u8, Vec<(GrandpaId, u64)>)>::decode_all(raw)
+        .map(|(_version, authorities)| authorities)
+        .or_else(|_| Vec::<(GrandpaId, u64)>::decode_all(raw))
+        .map(|authorities| to_raw_json(&authorities))
+        .map_err(|e| e.what().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain_spec::tests::{custom, temp_file, VALID_PK};
+    use crate::chain_spec::Chain;
+    use crate::token::{Allocation, TokenSpec};
+    use parity_scale_codec::Encode;
+
+    #[test]
+    fn t_decode_ved() {
+        let spec = Chain::Ved { wasm: None }
+            .generate()
+            .unwrap()
+            .to_json(true)
+            .unwrap();
+        let path = temp_file("t_decode_ved.json", spec);
+        let decoded: serde_json::Value =
+            serde_json::from_str(&decode(path.clone(), &[]).unwrap()).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        let alice = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+        assert_eq!(decoded["Sudo"]["Key"], alice);
+        assert_eq!(decoded["Erc20"]["TokenId"], 2);
+        assert_eq!(decoded["Erc20"]["Tokens"]["0"]["ticker"], "PSTABLE1");
+        assert_eq!(decoded["Erc20"]["Tokens"]["1"]["ticker"], "PSTABLE2");
+        assert!(decoded["Erc20"]["BalanceOf"][format!("[0,\"{}\"]", alice)].is_number());
+        assert!(decoded["Balances"]["FreeBalance"][alice].is_number());
+        assert_eq!(decoded["Babe"]["Authorities"][0][0], alice);
+        assert_eq!(decoded["Babe"]["Authorities"][0][1], 1);
+        assert_eq!(decoded["Grandpa"]["Authorities"][0][1], 1);
+        assert!(decoded["System"]["Code"]["bytes"].as_u64().unwrap() > 0);
+    }
+
+    #[test]
+    fn t_decode_extra_account() {
+        let account = substrate_warmup_common::parse_accountid32(VALID_PK).unwrap();
+        let spec = Chain::Ved { wasm: None }.generate().unwrap();
+        let (mut storage, _) = spec.build_storage().unwrap();
+        storage.insert(
+            map::<srml_balances::FreeBalance<Runtime>, _, u128>(&account),
+            5u128.encode(),
+        );
+
+        let raw_key = hex(&map::<srml_balances::FreeBalance<Runtime>, _, u128>(
+            &account,
+        ));
+
+        let without = Decoded::new(&storage, &KnownKeys::new(&[&storage], &[]));
+        assert_eq!(without.unknown[&raw_key], hex(&5u128.encode()));
+
+        let with = Decoded::new(&storage, &KnownKeys::new(&[&storage], &[account.clone()]));
+        assert!(!with.unknown.contains_key(&raw_key));
+        match &with.modules["Balances"]["FreeBalance"] {
+            Item::Map(balances) => assert_eq!(balances[&label(&account)].get(), "5"),
+            Item::Value(_) => panic!("FreeBalance is a map"),
+        }
+    }
+
+    #[test]
+    fn t_decode_genesis_accounts() {
+        let allocated = AccountId32::from([0x11; 32]);
+        let mut chain = custom();
+        if let Chain::Custom { tokens, .. } = &mut chain {
+            *tokens = vec![TokenSpec {
+                name: "Dock Dollar".to_string(),
+                ticker: "DD".to_string(),
+                total_supply: 100,
+                holder: DevAccount::Alice.account_id(),
+                allocations: vec![Allocation {
+                    account: allocated.clone(),
+                    amount: 7,
+                }],
+            }];
+        }
+        let spec = chain.generate().unwrap();
+        let path = temp_file(
+            "t_decode_genesis_accounts.json",
+            spec.to_json(false).unwrap(),
+        );
+        assert!(genesis_accounts(&path).unwrap().contains(&allocated));
+        let decoded: serde_json::Value =
+            serde_json::from_str(&decode(path.clone(), &[]).unwrap()).unwrap();
+        assert_eq!(decoded["Erc20"]["BalanceOf"][label(&(0u32, &allocated))], 7);
+
+        std::fs::write(&path, spec.to_json(true).unwrap()).unwrap();
+        assert_eq!(genesis_accounts(&path).unwrap(), vec![]);
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn t_sudo_key_raw_key() {
+        // the sudo genesis config sets nothing but the key
+        let (storage, _) = node_template_runtime::SudoConfig {
+            key: DevAccount::Alice.account_id(),
+        }
+        .build_storage()
+        .unwrap();
+        let keys: Vec<_> = storage.keys().cloned().collect();
+        assert_eq!(keys, vec![sudo_key_raw_key()]);
+    }
+
+    #[test]
+    fn t_decode_vesting() {
+        let alice = DevAccount::Alice.account_id();
+        let spec = Chain::Ved { wasm: None }.generate().unwrap();
+        let (mut storage, _) = spec.build_storage().unwrap();
+        storage.insert(
+            map::<srml_balances::Vesting<Runtime>, _, VestingSchedule<u128, u32>>(&alice),
+            VestingSchedule {
+                locked: 100u128,
+                per_block: 10u128,
+                starting_block: 5u32,
+            }
+            .encode(),
+        );
+
+        let decoded = Decoded::new(&storage, &KnownKeys::new(&[&storage], &[]));
+        match &decoded.modules["Balances"]["Vesting"] {
+            Item::Map(vesting) => assert_eq!(
+                vesting[&label(&alice)].get(),
+                r#"{"locked":100,"per_block":10,"starting_block":5}"#
+            ),
+            Item::Value(_) => panic!("Vesting is a map"),
+        }
+    }
+}
@@ -0,0 +1,198 @@
+//! Module by module comparison of the genesis storage of two chainspecs.
+
+use crate::decode::{
+    code_summary, genesis_accounts, genesis_storage, hex, to_raw_json, Json, KnownKeys, Storage,
+};
+use serde::Serialize;
+use sr_primitives::AccountId32;
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::PathBuf;
+
+/// Storage entries which could not be attributed to a known storage item are reported under
+/// this module name.
+const UNKNOWN: &str = "unknown";
+
+#[derive(Serialize)]
+pub struct Diff {
+    /// The runtime wasm blob is summarized rather than diffed.
+    pub code: CodeDiff,
+    #[serde(flatten)]
+    pub modules: BTreeMap<&'static str, ModuleDiff>,
+}
+
+#[derive(Serialize)]
+pub struct CodeDiff {
+    pub changed: bool,
+    pub old: Option<Json>,
+    pub new: Option<Json>,
+}
+
+#[derive(Serialize, Default)]
+pub struct ModuleDiff {
+    pub added: Vec<Entry>,
+    pub removed: Vec<Entry>,
+    pub changed: Vec<Change>,
+}
+
+#[derive(Serialize)]
+pub struct Entry {
+    pub item: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    pub value: Json,
+}
+
+#[derive(Serialize)]
+pub struct Change {
+    pub item: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub key: Option<String>,
+    pub old: Json,
+    pub new: Json,
+}
+
+impl Diff {
+    pub fn new(old: &Storage, new: &Storage, accounts: &[AccountId32]) -> Self {
+        let known = KnownKeys::new(&[old, new], accounts);
+        let code = b":code".as_ref();
+        let mut modules: BTreeMap<&'static str, ModuleDiff> = BTreeMap::new();
+
+        let keys: BTreeSet<&Vec<u8>> = old.keys().chain(new.keys()).collect();
+        for raw_key in keys.into_iter().filter(|k| k.as_slice() != code) {
+            let (old_value, new_value) = (old.get(raw_key), new.get(raw_key));
+            if old_value == new_value {
+                continue;
+            }
+            let (module, item, key) = match known.get(raw_key) {
+                Some(k) => (k.module, k.item.to_string(), k.key.clone()),
+                None => (UNKNOWN, hex(raw_key), None),
+            };
+            let decode = |raw: &Vec<u8>| -> Json {
+                known
+                    .get(raw_key)
+                    .and_then(|k| k.decode(raw).ok())
+                    .unwrap_or_else(|| to_raw_json(&hex(raw)))
+            };
+            let diff = modules.entry(module).or_default();
+            match (old_value, new_value) {
+                (None, Some(value)) => diff.added.push(Entry {
+                    item,
+                    key,
+                    value: decode(value),
+                }),
+                (Some(value), None) => diff.removed.push(Entry {
+                    item,
+                    key,
+                    value: decode(value),
+                }),
+                (Some(old_value), Some(new_value)) => diff.changed.push(Change {
+                    item,
+                    key,
+                    old: decode(old_value),
+                    new: decode(new_value),
+                }),
+                (None, None) => unreachable!("key was taken from one of the storages"),
+            }
+        }
+
+        let summary = |storage: &Storage| {
+            storage
+                .get(code)
+                .map(|c| code_summary(c).expect("code summary never fails"))
+        };
+        Diff {
+            code: CodeDiff {
+                changed: old.get(code) != new.get(code),
+                old: summary(old),
+                new: summary(new),
+            },
+            modules,
+        }
+    }
+}
+
+/// Compare the genesis storage of two chainspec files, output the result as json.
+pub fn diff(old: PathBuf, new: PathBuf, accounts: &[AccountId32]) -> Result<String, String> {
+    let mut accounts = accounts.to_vec();
+    accounts.extend(genesis_accounts(&old)?);
+    accounts.extend(genesis_accounts(&new)?);
+    let old = genesis_storage(old)?;
+    let new = genesis_storage(new)?;
+    serde_json::to_string_pretty(&Diff::new(&old, &new, &accounts)).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain_spec::tests::{custom, VALID_PK};
+    use crate::chain_spec::Chain;
+    use sr_primitives::BuildStorage;
+    use substrate_warmup_common::DevAccount;
+
+    fn ved_storage() -> Storage {
+        Chain::Ved { wasm: None }
+            .generate()
+            .unwrap()
+            .build_storage()
+            .unwrap()
+            .0
+    }
+
+    #[test]
+    fn t_diff_identical() {
+        let diff = Diff::new(&ved_storage(), &ved_storage(), &[]);
+        assert!(!diff.code.changed);
+        assert!(diff.modules.is_empty());
+    }
+
+    #[test]
+    fn t_diff_changes() {
+        let old = ved_storage();
+        let mut new = ved_storage();
+        let unknown_key = b"not a real storage key".to_vec();
+        new.insert(unknown_key.clone(), vec![1, 2, 3]);
+        new.insert(b":code".to_vec(), vec![0, 1, 2]);
+
+        let diff = Diff::new(&old, &new, &[]);
+        assert!(diff.code.changed);
+        assert_eq!(
+            diff.code.new.unwrap().get(),
+            code_summary(&[0, 1, 2]).unwrap().get()
+        );
+        assert_eq!(diff.modules.len(), 1);
+        let unknown = &diff.modules[UNKNOWN];
+        assert_eq!(unknown.added.len(), 1);
+        assert_eq!(unknown.added[0].item, hex(&unknown_key));
+        assert_eq!(unknown.added[0].value.get(), "\"0x010203\"");
+        assert!(unknown.removed.is_empty());
+        assert!(unknown.changed.is_empty());
+
+        let diff = Diff::new(&new, &old, &[]);
+        assert_eq!(diff.modules[UNKNOWN].removed.len(), 1);
+    }
+
+    #[test]
+    fn t_diff_decoded() {
+        let new_sudo = substrate_warmup_common::parse_accountid32(VALID_PK).unwrap();
+        let new = custom().generate().unwrap().build_storage().unwrap().0;
+
+        let diff = Diff::new(&ved_storage(), &new, &[]);
+        assert!(!diff.code.changed);
+
+        let sudo = &diff.modules["Sudo"].changed;
+        assert_eq!(sudo.len(), 1);
+        assert_eq!(sudo[0].item, "Key");
+        assert_eq!(
+            sudo[0].old.get(),
+            format!("\"{}\"", DevAccount::Alice.account_id())
+        );
+
+        let balances = &diff.modules["Balances"];
+        assert!(balances.removed.iter().any(|e| e.item == "FreeBalance"
+            && e.key == Some(DevAccount::Alice.account_id().to_string())));
+        assert!(balances
+            .added
+            .iter()
+            .any(|e| e.item == "FreeBalance" && e.key == Some(new_sudo.to_string())));
+    }
+}
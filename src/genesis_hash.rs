@@ -0,0 +1,83 @@
+//! Offline computation of the genesis block a chainspec describes, so operators can confirm
+//! they all run the same chain before any node is started.
+
+use node_template_runtime::{Block, GenesisConfig};
+use serde::Serialize;
+use sr_primitives::traits::{Block as BlockT, Hash as HashT, Header as HeaderT};
+use sr_primitives::BuildStorage;
+use std::path::PathBuf;
+use substrate_chain_spec::ChainSpec;
+use substrate_client::genesis::construct_genesis_block;
+use substrate_primitives::H256;
+
+type Hashing = <<Block as BlockT>::Header as HeaderT>::Hashing;
+
+#[derive(Debug, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GenesisHash {
+    pub state_root: H256,
+    pub genesis_hash: H256,
+}
+
+/// State root and genesis block hash of a chainspec file, raw or not. Computed the same way a
+/// node computes them when it first starts.
+pub fn genesis_hash(path: PathBuf) -> Result<GenesisHash, String> {
+    let spec = ChainSpec::<GenesisConfig>::from_json_file(path)?;
+    let (top, children) = spec.build_storage()?;
+    // nodes store the root of each child trie in the top trie, our runtime never creates any
+    if !children.is_empty() {
+        return Err("genesis storage contains child tries, which are not supported".into());
+    }
+    let state_root = Hashing::trie_root(top.into_iter().collect());
+    let block: Block = construct_genesis_block(state_root);
+    Ok(GenesisHash {
+        state_root,
+        genesis_hash: block.header().hash(),
+    })
+}
+
+/// Output the genesis hash of a chainspec as json, failing when it differs from `expected`.
+pub fn run(path: PathBuf, expected: Option<H256>) -> Result<String, String> {
+    let computed = genesis_hash(path)?;
+    if let Some(expected) = expected {
+        if computed.genesis_hash != expected {
+            return Err(format!(
+                "genesis hash mismatch: chainspec has {:?}, expected {:?}",
+                computed.genesis_hash, expected
+            ));
+        }
+    }
+    serde_json::to_string_pretty(&computed).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain_spec::tests::temp_file;
+    use crate::chain_spec::Chain;
+
+    fn write(name: &str, raw: bool) -> PathBuf {
+        let spec = Chain::Ved { wasm: None }
+            .generate()
+            .unwrap()
+            .to_json(raw)
+            .unwrap();
+        temp_file(&format!("{}.json", name), spec)
+    }
+
+    #[test]
+    fn t_genesis_hash() {
+        let raw = write("t_genesis_hash_raw", true);
+        let plain = write("t_genesis_hash_plain", false);
+        let computed = genesis_hash(raw.clone()).unwrap();
+        assert_eq!(genesis_hash(plain.clone()).unwrap(), computed);
+        assert_ne!(computed.state_root, computed.genesis_hash);
+
+        run(raw.clone(), Some(computed.genesis_hash)).unwrap();
+        let err = run(raw.clone(), Some(H256::zero())).unwrap_err();
+        assert!(err.contains("genesis hash mismatch"));
+
+        std::fs::remove_file(raw).unwrap();
+        std::fs::remove_file(plain).unwrap();
+    }
+}
@@ -0,0 +1,270 @@
+//! Validator key generation. All keys are derived from a single secret phrase so the full set can
+//! be recovered from the phrase alone.
+
+use serde::Serialize;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+#[cfg(unix)]
+use std::os::unix::fs::OpenOptionsExt;
+use std::path::{Path, PathBuf};
+use structopt::StructOpt;
+use substrate_primitives::crypto::{key_types, KeyTypeId};
+use substrate_primitives::{ed25519, sr25519, Pair};
+
+/// Derivation paths appended to the phrase. The account key is the phrase itself so it can be
+/// imported into wallets as is.
+const BABE_PATH: &str = "//babe";
+const GRANDPA_PATH: &str = "//grandpa";
+
+#[derive(StructOpt)]
+pub struct Keygen {
+    /// Derive keys from this mnemonic, or any other secret uri such as `//Alice`, instead of
+    /// generating a new mnemonic.
+    #[structopt(long)]
+    phrase: Option<String>,
+    /// Write the secret keys into this directory in the keystore format used by substrate. A
+    /// node reads its keys from `<base-path>/chains/<chain-id>/keystore`.
+    #[structopt(long, parse(from_os_str))]
+    keystore: Option<PathBuf>,
+}
+
+/// Public keys, 0x prefixed hex.
+#[derive(Serialize)]
+pub struct Keys {
+    /// Only shown when the phrase was generated, keep it secret.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phrase: Option<String>,
+    pub babe: String,
+    pub grandpa: String,
+    pub account: String,
+    /// The babe and grandpa keys in the format accepted by `custom --validator`.
+    pub validator: String,
+}
+
+impl Keygen {
+    pub fn run(self) -> Result<String, String> {
+        let keys = self.keys()?;
+        serde_json::to_string_pretty(&keys).map_err(|e| e.to_string())
+    }
+
+    fn keys(self) -> Result<Keys, String> {
+        let (phrase, generated) = match self.phrase {
+            Some(phrase) => (phrase, false),
+            None => (sr25519::Pair::generate_with_phrase(None).1, true),
+        };
+        let account = pair::<sr25519::Pair>(&phrase)?.public();
+        let babe_suri = format!("{}{}", phrase, BABE_PATH);
+        let babe = pair::<sr25519::Pair>(&babe_suri)?.public();
+        let grandpa_suri = format!("{}{}", phrase, GRANDPA_PATH);
+        let grandpa = pair::<ed25519::Pair>(&grandpa_suri)?.public();
+
+        if let Some(keystore) = &self.keystore {
+            fs::create_dir_all(keystore)
+                .map_err(|e| format!("could not create {}: {}", keystore.display(), e))?;
+            write_keystore(
+                keystore,
+                &[
+                    (key_types::BABE, &babe_suri, babe.as_ref()),
+                    (key_types::GRANDPA, &grandpa_suri, grandpa.as_ref()),
+                    (key_types::ACCOUNT, &phrase, account.as_ref()),
+                ],
+            )?;
+        }
+
+        let (babe, grandpa) = (hex(babe.as_ref()), hex(grandpa.as_ref()));
+        Ok(Keys {
+            phrase: if generated { Some(phrase) } else { None },
+            validator: format!("{},{}", grandpa, babe),
+            babe,
+            grandpa,
+            account: hex(account.as_ref()),
+        })
+    }
+}
+
+fn pair<P: Pair>(suri: &str) -> Result<P, String> {
+    P::from_string(suri, None).map_err(|e| format!("invalid phrase: {:?}", e))
+}
+
+/// Write all keys or none: if one can not be written, the files written before it are removed.
+fn write_keystore(keystore: &Path, keys: &[(KeyTypeId, &str, &[u8])]) -> Result<(), String> {
+    let mut written = Vec::new();
+    for (key_type, suri, public) in keys {
+        match insert(keystore, *key_type, suri, public) {
+            Ok(path) => written.push(path),
+            Err(e) => {
+                for path in written {
+                    let _ = fs::remove_file(path);
+                }
+                return Err(e);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Write a key the way `substrate_keystore::Store` does: the file is named after the key type and
+/// public key, and contains the secret uri as a json string. On unix the file is only readable by
+/// its owner. An existing key file is never overwritten. Returns the path of the new file.
+fn insert(
+    keystore: &Path,
+    key_type: KeyTypeId,
+    suri: &str,
+    public: &[u8],
+) -> Result<PathBuf, String> {
+    let path = keystore.join(format!(
+        "{}{}",
+        hex::encode(key_type.0),
+        hex::encode(public)
+    ));
+    let contents = serde_json::to_string(suri).expect("in-memory serialization failed");
+    let error = |e: std::io::Error| format!("could not write {}: {}", path.display(), e);
+
+    let mut options = OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    options.mode(0o600);
+    let mut file = options.open(&path).map_err(error)?;
+    if let Err(e) = file.write_all(contents.as_bytes()) {
+        let _ = fs::remove_file(&path);
+        return Err(error(e));
+    }
+    Ok(path)
+}
+
+fn hex(raw: &[u8]) -> String {
+    format!("0x{}", hex::encode(raw))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::validator::parse_validator;
+    #[cfg(unix)]
+    use std::os::unix::fs::PermissionsExt;
+    use substrate_warmup_common::{parse_accountid32, DevAccount};
+
+    #[test]
+    fn t_keygen_generate() {
+        let keys = Keygen {
+            phrase: None,
+            keystore: None,
+        }
+        .keys()
+        .unwrap();
+        let phrase = keys.phrase.unwrap();
+        assert_eq!(phrase.split_whitespace().count(), 12);
+
+        let again = Keygen {
+            phrase: Some(phrase),
+            keystore: None,
+        }
+        .keys()
+        .unwrap();
+        assert!(again.phrase.is_none());
+        assert_eq!(again.babe, keys.babe);
+        assert_eq!(again.grandpa, keys.grandpa);
+        assert_eq!(again.account, keys.account);
+    }
+
+    #[test]
+    fn t_keygen_keys() {
+        let keys = Keygen {
+            phrase: Some("//Alice".into()),
+            keystore: None,
+        }
+        .keys()
+        .unwrap();
+        assert_eq!(
+            parse_accountid32(&keys.account).unwrap(),
+            DevAccount::Alice.account_id()
+        );
+        let validator = parse_validator(&keys.validator).unwrap();
+        assert_eq!(
+            validator.babe.as_ref(),
+            pair::<sr25519::Pair>("//Alice//babe")
+                .unwrap()
+                .public()
+                .as_ref()
+        );
+        assert_eq!(
+            validator.grandpa.as_ref(),
+            pair::<ed25519::Pair>("//Alice//grandpa")
+                .unwrap()
+                .public()
+                .as_ref()
+        );
+        assert_ne!(keys.babe, keys.account);
+    }
+
+    #[test]
+    fn t_keygen_invalid_phrase() {
+        Keygen {
+            phrase: Some("not a valid mnemonic".into()),
+            keystore: None,
+        }
+        .keys()
+        .unwrap_err();
+    }
+
+    #[test]
+    fn t_keygen_keystore() {
+        let keystore = std::env::temp_dir().join("substrate-warmup-t_keygen_keystore");
+        let _ = fs::remove_dir_all(&keystore);
+        let keys = Keygen {
+            phrase: Some("//Alice".into()),
+            keystore: Some(keystore.clone()),
+        }
+        .keys()
+        .unwrap();
+
+        let read = |key_type: KeyTypeId, public: &str| -> String {
+            let name = format!("{}{}", hex::encode(key_type.0), &public[2..]);
+            serde_json::from_str(&fs::read_to_string(keystore.join(name)).unwrap()).unwrap()
+        };
+        assert_eq!(read(key_types::BABE, &keys.babe), "//Alice//babe");
+        assert_eq!(read(key_types::GRANDPA, &keys.grandpa), "//Alice//grandpa");
+        assert_eq!(read(key_types::ACCOUNT, &keys.account), "//Alice");
+        assert_eq!(fs::read_dir(&keystore).unwrap().count(), 3);
+        #[cfg(unix)]
+        for entry in fs::read_dir(&keystore).unwrap() {
+            let mode = entry.unwrap().metadata().unwrap().permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        let err = Keygen {
+            phrase: Some("//Alice".into()),
+            keystore: Some(keystore.clone()),
+        }
+        .keys()
+        .unwrap_err();
+        assert!(err.contains("could not write"), "{}", err);
+        assert_eq!(read(key_types::BABE, &keys.babe), "//Alice//babe");
+        fs::remove_dir_all(keystore).unwrap();
+    }
+
+    #[test]
+    fn t_keygen_keystore_partial() {
+        let keystore = std::env::temp_dir().join("substrate-warmup-t_keygen_keystore_partial");
+        let _ = fs::remove_dir_all(&keystore);
+        fs::create_dir_all(&keystore).unwrap();
+        let grandpa = pair::<ed25519::Pair>("//Alice//grandpa").unwrap().public();
+        let taken = keystore.join(format!(
+            "{}{}",
+            hex::encode(key_types::GRANDPA.0),
+            hex::encode(grandpa.as_ref())
+        ));
+        fs::write(&taken, "taken").unwrap();
+
+        Keygen {
+            phrase: Some("//Alice".into()),
+            keystore: Some(keystore.clone()),
+        }
+        .keys()
+        .unwrap_err();
+        // the babe key written before the failure is removed, the existing file is left alone
+        assert_eq!(fs::read_dir(&keystore).unwrap().count(), 1);
+        assert_eq!(fs::read_to_string(&taken).unwrap(), "taken");
+        fs::remove_dir_all(keystore).unwrap();
+    }
+}
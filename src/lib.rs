@@ -0,0 +1,29 @@
+//! Generation and inspection of chainspecs for the substrate warmup runtime.
+//!
+//! Chainspecs are built with `ChainSpecBuilder`. `Chain` describes the chains available on the
+//! command line and turns them into builders.
+
+pub mod allocation;
+pub mod builder;
+pub mod chain_spec;
+pub mod config;
+pub mod decode;
+pub mod diff;
+pub mod genesis_hash;
+pub mod keygen;
+pub mod network;
+pub mod node_key;
+pub mod token;
+pub mod validate;
+pub mod validator;
+pub mod vesting;
+pub mod wasm;
+
+pub use builder::ChainSpecBuilder;
+pub use chain_spec::{dev_validator, Chain};
+pub use network::{ChainProperties, Network, Telemetry};
+pub use node_key::BootnodeKey;
+pub use substrate_warmup_common::DevAccount;
+pub use token::{Allocation, TokenSpec};
+pub use validator::Validator;
+pub use vesting::Vesting;
@@ -1,7 +1,73 @@
-mod chain_spec;
+use sr_primitives::AccountId32;
+use std::path::PathBuf;
+use structopt::StructOpt;
+use substrate_primitives::H256;
+use substrate_warmup_chaingen::{chain_spec, decode, diff, genesis_hash, keygen};
+use substrate_warmup_common::{parse_accountid32, parse_hash};
+
+#[derive(StructOpt)]
+/// generate and inspect substrate chainspecs
+enum Command {
+    #[structopt(flatten)]
+    Generate(chain_spec::Chain),
+    /// Print the genesis storage of a chainspec as human readable json
+    Decode {
+        #[structopt(parse(from_os_str))]
+        chainspec: PathBuf,
+        /// Additional accounts to look for in storage maps. Dev accounts, the sudo key and
+        /// babe authorities are always looked for. May be repeated.
+        #[structopt(long = "account", parse(try_from_str = parse_accountid32))]
+        accounts: Vec<AccountId32>,
+    },
+    /// Compare the genesis storage of two chainspecs module by module
+    Diff {
+        #[structopt(parse(from_os_str))]
+        old: PathBuf,
+        #[structopt(parse(from_os_str))]
+        new: PathBuf,
+        /// Additional accounts to look for in storage maps, as with `decode`. May be repeated.
+        #[structopt(long = "account", parse(try_from_str = parse_accountid32))]
+        accounts: Vec<AccountId32>,
+    },
+    /// Generate babe, grandpa and account keys for a validator
+    Keygen(keygen::Keygen),
+    /// Compute the genesis state root and block hash of a chainspec without running a node
+    GenesisHash {
+        #[structopt(parse(from_os_str))]
+        chainspec: PathBuf,
+        /// Fail unless the chainspec has this genesis hash.
+        #[structopt(long, parse(try_from_str = parse_hash))]
+        verify: Option<H256>,
+    },
+}
 
 #[paw::main]
-fn main(chain: chain_spec::Chain) {
-    let spec = chain.generate();
-    println!("{}", spec.to_json(true).unwrap());
+fn main(command: Command) {
+    let output = run(command).unwrap_or_else(|e| {
+        eprintln!("{}", e);
+        std::process::exit(1);
+    });
+    println!("{}", output);
+}
+
+fn run(command: Command) -> Result<String, String> {
+    match command {
+        Command::Generate(chain) => {
+            let builder = chain.builder()?;
+            let bootnode_keys = builder.bootnode_keys().to_vec();
+            let spec = builder.build()?.to_json(true)?;
+            // stdout is the chainspec, tell operators how to start the bootnodes on stderr
+            for key in bootnode_keys {
+                eprintln!("start bootnode {} with {}", key.host, key.flag());
+            }
+            Ok(spec)
+        }
+        Command::Decode {
+            chainspec,
+            accounts,
+        } => decode::decode(chainspec, &accounts),
+        Command::Diff { old, new, accounts } => diff::diff(old, new, &accounts),
+        Command::Keygen(keygen) => keygen.run(),
+        Command::GenesisHash { chainspec, verify } => genesis_hash::run(chainspec, verify),
+    }
 }
@@ -0,0 +1,232 @@
+//! Settings written to the chainspec alongside genesis: bootnodes, telemetry endpoints and the
+//! chain properties read by UIs such as polkadot.js.
+
+use crate::node_key::{parse_bootnode_key, BootnodeKey};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use structopt::StructOpt;
+use substrate_chain_spec::Properties;
+use substrate_network::config::parse_str_addr;
+use substrate_network::Multiaddr;
+use substrate_telemetry::TelemetryEndpoints;
+
+#[derive(
+    StructOpt, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Debug, Serialize, Deserialize,
+)]
+pub struct Network {
+    /// Multiaddr of a node new nodes should connect to, including its peer id, e.g.
+    /// `/ip4/127.0.0.1/tcp/30333/p2p/QmRpheLN4JWdAnY7HGJfWFNbfkQCb6tFf4vvA6hgjMZKrR`. May be
+    /// repeated.
+    #[structopt(long = "bootnode")]
+    pub bootnodes: Vec<String>,
+    /// Bootnode to generate a node key for, formatted as `<node-key-file>,<host>[,<port>]`. The
+    /// key is read from the file, or written to it when missing, and the bootnode's multiaddr is
+    /// added to the chainspec. May be repeated.
+    #[structopt(long = "bootnode-key", parse(try_from_str = parse_bootnode_key))]
+    pub bootnode_keys: Vec<BootnodeKey>,
+    /// Telemetry endpoint and verbosity, formatted as `<url> <verbosity>`, e.g.
+    /// `'wss://telemetry.polkadot.io/submit 0'`. May be repeated.
+    #[structopt(long = "telemetry-url", parse(try_from_str = parse_telemetry))]
+    pub telemetry: Vec<Telemetry>,
+    #[structopt(flatten)]
+    pub properties: ChainProperties,
+}
+
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Telemetry {
+    pub url: String,
+    pub verbosity: u8,
+}
+
+/// Chain properties, shown to users by wallets and block explorers.
+#[derive(
+    StructOpt, Clone, Default, Eq, PartialEq, Ord, PartialOrd, Debug, Serialize, Deserialize,
+)]
+#[serde(deny_unknown_fields)]
+pub struct ChainProperties {
+    /// Symbol of the native token, e.g. `DOCK`.
+    #[structopt(long)]
+    pub token_symbol: Option<String>,
+    /// Number of decimal places used when displaying native token amounts.
+    #[structopt(long)]
+    pub token_decimals: Option<u8>,
+    /// Address format used when displaying ss58 addresses.
+    #[structopt(long)]
+    pub ss58_format: Option<u8>,
+}
+
+impl Network {
+    /// Add the multiaddrs of bootnodes given by key to the bootnodes, generating missing keys.
+    pub fn resolve_bootnode_keys(&mut self) -> Result<(), String> {
+        for key in &self.bootnode_keys {
+            self.bootnodes.push(key.multiaddr()?);
+        }
+        self.bootnode_keys.clear();
+        Ok(())
+    }
+
+    /// Reject bootnodes and telemetry endpoints a node would fail to use.
+    pub fn check(&self) -> Result<(), String> {
+        for bootnode in &self.bootnodes {
+            parse_str_addr(bootnode)
+                .map_err(|e| format!("invalid bootnode {:?}: {}", bootnode, e))?;
+        }
+        for telemetry in &self.telemetry {
+            check_telemetry_url(&telemetry.url)?;
+        }
+        Ok(())
+    }
+
+    pub fn telemetry_endpoints(&self) -> Option<TelemetryEndpoints> {
+        if self.telemetry.is_empty() {
+            return None;
+        }
+        Some(TelemetryEndpoints::new(
+            self.telemetry
+                .iter()
+                .map(|t| (t.url.clone(), t.verbosity))
+                .collect(),
+        ))
+    }
+}
+
+impl ChainProperties {
+    /// Properties as they appear in the chainspec, keys are camelCase.
+    pub fn to_properties(&self) -> Option<Properties> {
+        let mut properties = Properties::new();
+        if let Some(symbol) = &self.token_symbol {
+            properties.insert("tokenSymbol".into(), Value::from(symbol.as_str()));
+        }
+        if let Some(decimals) = self.token_decimals {
+            properties.insert("tokenDecimals".into(), Value::from(decimals));
+        }
+        if let Some(format) = self.ss58_format {
+            properties.insert("ss58Format".into(), Value::from(format));
+        }
+        if properties.is_empty() {
+            None
+        } else {
+            Some(properties)
+        }
+    }
+}
+
+/// parse a telemetry endpoint from `<url> <verbosity>`
+pub fn parse_telemetry(imp: &str) -> Result<Telemetry, String> {
+    match imp.split_whitespace().collect::<Vec<&str>>().as_slice() {
+        [url, verbosity] => Ok(Telemetry {
+            url: url.to_string(),
+            verbosity: verbosity
+                .parse()
+                .map_err(|e| format!("invalid telemetry verbosity {:?}: {}", verbosity, e))?,
+        }),
+        _ => Err("telemetry endpoint should be formatted as '<url> <verbosity>'".into()),
+    }
+}
+
+/// Telemetry endpoints are either websocket urls or multiaddrs.
+fn check_telemetry_url(url: &str) -> Result<(), String> {
+    if url.starts_with('/') {
+        return url
+            .parse::<Multiaddr>()
+            .map(|_| ())
+            .map_err(|e| format!("invalid telemetry multiaddr {:?}: {}", url, e));
+    }
+    let host = ["wss://", "ws://"]
+        .iter()
+        .find(|scheme| url.starts_with(*scheme))
+        .map(|scheme| &url[scheme.len()..])
+        .ok_or_else(|| format!("telemetry url {:?} should start with ws:// or wss://", url))?;
+    if host.is_empty() || host.starts_with('/') {
+        return Err(format!("telemetry url {:?} has no host", url));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BOOTNODE: &str =
+        "/ip4/127.0.0.1/tcp/30333/p2p/QmRpheLN4JWdAnY7HGJfWFNbfkQCb6tFf4vvA6hgjMZKrR";
+
+    #[test]
+    fn t_check_bootnodes() {
+        let network = |bootnode: &str| Network {
+            bootnodes: vec![bootnode.to_string()],
+            ..Default::default()
+        };
+        network(BOOTNODE).check().unwrap();
+        network("/dns4/example.com/tcp/30333/p2p/QmRpheLN4JWdAnY7HGJfWFNbfkQCb6tFf4vvA6hgjMZKrR")
+            .check()
+            .unwrap();
+        for invalid in &[
+            "",
+            "127.0.0.1:30333",
+            "/ip4/127.0.0.1/tcp/30333",
+            "/ip4/127.0.0.1/tcp/30333/p2p/notapeerid",
+            "/ip4/300.0.0.1/tcp/30333/p2p/QmRpheLN4JWdAnY7HGJfWFNbfkQCb6tFf4vvA6hgjMZKrR",
+        ] {
+            network(invalid).check().unwrap_err();
+        }
+    }
+
+    #[test]
+    fn t_parse_telemetry() {
+        assert_eq!(
+            parse_telemetry("wss://telemetry.polkadot.io/submit 0").unwrap(),
+            Telemetry {
+                url: "wss://telemetry.polkadot.io/submit".into(),
+                verbosity: 0,
+            }
+        );
+        parse_telemetry("wss://telemetry.polkadot.io/submit").unwrap_err();
+        parse_telemetry("wss://telemetry.polkadot.io/submit 256").unwrap_err();
+        parse_telemetry("wss://telemetry.polkadot.io/submit 0 1").unwrap_err();
+    }
+
+    #[test]
+    fn t_check_telemetry() {
+        let network = |url: &str| Network {
+            telemetry: vec![Telemetry {
+                url: url.to_string(),
+                verbosity: 0,
+            }],
+            ..Default::default()
+        };
+        network("wss://telemetry.polkadot.io/submit")
+            .check()
+            .unwrap();
+        network("ws://127.0.0.1:8000/submit").check().unwrap();
+        network("/dns4/telemetry.polkadot.io/tcp/443/x-parity-wss/%2Fsubmit%2F")
+            .check()
+            .unwrap();
+        network("https://telemetry.polkadot.io/submit")
+            .check()
+            .unwrap_err();
+        network("wss://").check().unwrap_err();
+        network("/notaprotocol").check().unwrap_err();
+        assert!(Network::default().telemetry_endpoints().is_none());
+    }
+
+    #[test]
+    fn t_properties() {
+        assert_eq!(ChainProperties::default().to_properties(), None);
+        let properties = ChainProperties {
+            token_symbol: Some("DOCK".into()),
+            token_decimals: Some(6),
+            ss58_format: Some(42),
+        }
+        .to_properties()
+        .unwrap();
+        assert_eq!(
+            Value::Object(properties),
+            serde_json::json!({
+                "tokenSymbol": "DOCK",
+                "tokenDecimals": 6,
+                "ss58Format": 42,
+            })
+        );
+    }
+}
@@ -0,0 +1,154 @@
+//! Stable libp2p identities for bootnodes. Each bootnode gets an ed25519 node key file, read by
+//! the node through `--node-key-file`, and its multiaddr is derived from the key's peer id.
+
+use serde::{Deserialize, Serialize};
+use std::net::IpAddr;
+use std::path::PathBuf;
+use substrate_network::config::{NodeKeyConfig, Secret};
+
+/// Port substrate nodes listen on for p2p connections unless told otherwise.
+pub const DEFAULT_P2P_PORT: u16 = 30333;
+
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct BootnodeKey {
+    /// File holding the node's secret key. A new key is generated when the file does not exist.
+    pub file: PathBuf,
+    /// Ip address or dns name the bootnode can be reached at.
+    pub host: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+}
+
+fn default_port() -> u16 {
+    DEFAULT_P2P_PORT
+}
+
+impl BootnodeKey {
+    /// Read the node key, generating it first if needed, and derive the bootnode's multiaddr.
+    /// Keys are stored the way substrate stores them, as 32 raw bytes.
+    pub fn multiaddr(&self) -> Result<String, String> {
+        if let Some(dir) = self.file.parent() {
+            std::fs::create_dir_all(dir)
+                .map_err(|e| format!("could not create {}: {}", dir.display(), e))?;
+        }
+        let keypair = NodeKeyConfig::Ed25519(Secret::File(self.file.clone()))
+            .into_keypair()
+            .map_err(|e| format!("invalid node key file {}: {}", self.file.display(), e))?;
+        let host = match self.host.parse::<IpAddr>() {
+            Ok(IpAddr::V4(ip)) => format!("/ip4/{}", ip),
+            Ok(IpAddr::V6(ip)) => format!("/ip6/{}", ip),
+            Err(_) => format!("/dns4/{}", self.host),
+        };
+        Ok(format!(
+            "{}/tcp/{}/p2p/{}",
+            host,
+            self.port,
+            keypair.public().into_peer_id()
+        ))
+    }
+
+    /// Flag the bootnode must be started with to use this key.
+    pub fn flag(&self) -> String {
+        format!("--node-key-file {}", self.file.display())
+    }
+}
+
+/// parse a bootnode key from `<node-key-file>,<host>[,<port>]`
+pub fn parse_bootnode_key(imp: &str) -> Result<BootnodeKey, String> {
+    let (file, host, port) = match imp.split(',').collect::<Vec<&str>>().as_slice() {
+        [file, host] => (*file, *host, DEFAULT_P2P_PORT),
+        [file, host, port] => (
+            *file,
+            *host,
+            port.parse()
+                .map_err(|e| format!("invalid port {:?}: {}", port, e))?,
+        ),
+        _ => {
+            return Err(
+                "bootnode key should be formatted as <node-key-file>,<host>[,<port>]".into(),
+            )
+        }
+    };
+    if file.is_empty() || host.is_empty() {
+        return Err("bootnode key needs both a node key file and a host".into());
+    }
+    Ok(BootnodeKey {
+        file: file.into(),
+        host: host.to_string(),
+        port,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::network::Network;
+    use std::fs;
+
+    fn key(name: &str, host: &str) -> BootnodeKey {
+        BootnodeKey {
+            file: std::env::temp_dir()
+                .join("substrate-warmup-node-keys")
+                .join(name),
+            host: host.to_string(),
+            port: DEFAULT_P2P_PORT,
+        }
+    }
+
+    #[test]
+    fn t_known_node_key() {
+        let key = key("t_known_node_key", "127.0.0.1");
+        let mut secret = [0u8; 32];
+        secret[31] = 1;
+        fs::create_dir_all(key.file.parent().unwrap()).unwrap();
+        fs::write(&key.file, secret).unwrap();
+        assert_eq!(
+            key.multiaddr().unwrap(),
+            "/ip4/127.0.0.1/tcp/30333/p2p/QmRpheLN4JWdAnY7HGJfWFNbfkQCb6tFf4vvA6hgjMZKrR"
+        );
+        fs::remove_file(&key.file).unwrap();
+    }
+
+    #[test]
+    fn t_generate_node_key() {
+        let key = key("t_generate_node_key", "alice");
+        let _ = fs::remove_file(&key.file);
+        let multiaddr = key.multiaddr().unwrap();
+        assert!(multiaddr.starts_with("/dns4/alice/tcp/30333/p2p/"));
+        assert_eq!(fs::read(&key.file).unwrap().len(), 32);
+        // the key is reused once generated
+        assert_eq!(key.multiaddr().unwrap(), multiaddr);
+        Network {
+            bootnodes: vec![multiaddr],
+            ..Default::default()
+        }
+        .check()
+        .unwrap();
+        fs::remove_file(&key.file).unwrap();
+    }
+
+    #[test]
+    fn t_parse_bootnode_key() {
+        assert_eq!(
+            parse_bootnode_key("keys/alice,10.0.0.1").unwrap(),
+            BootnodeKey {
+                file: "keys/alice".into(),
+                host: "10.0.0.1".into(),
+                port: DEFAULT_P2P_PORT,
+            }
+        );
+        assert_eq!(
+            parse_bootnode_key("keys/alice,::1,30334").unwrap().port,
+            30334
+        );
+        for invalid in &[
+            "keys/alice",
+            ",10.0.0.1",
+            "keys/alice,",
+            "keys/alice,10.0.0.1,x",
+        ] {
+            parse_bootnode_key(invalid).unwrap_err();
+        }
+    }
+}
@@ -0,0 +1,207 @@
+use crate::config::{account, amount};
+use erc20::{Erc20Token, MAX_NAME_LEN, MAX_TICKER_LEN};
+use node_template_runtime::Erc20Config;
+use serde::{Deserialize, Serialize};
+use sr_primitives::AccountId32;
+use std::collections::BTreeSet;
+use substrate_warmup_common::parse_accountid32;
+
+/// An erc20 token minted at genesis.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TokenSpec {
+    pub name: String,
+    pub ticker: String,
+    #[serde(deserialize_with = "amount")]
+    pub total_supply: u128,
+    /// Receives whatever part of the supply is not allocated to other accounts.
+    #[serde(deserialize_with = "account")]
+    pub holder: AccountId32,
+    /// Portions of the supply given to accounts other than the holder.
+    #[serde(default)]
+    pub allocations: Vec<Allocation>,
+}
+
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Allocation {
+    #[serde(deserialize_with = "account")]
+    pub account: AccountId32,
+    #[serde(deserialize_with = "amount")]
+    pub amount: u128,
+}
+
+/// Contents of a file passed as `--tokens-file`.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TokenFile {
+    pub tokens: Vec<TokenSpec>,
+}
+
+/// parse a token from `<name>,<ticker>,<total-supply>,<holder>`
+pub fn parse_token(imp: &str) -> Result<TokenSpec, String> {
+    match imp.split(',').collect::<Vec<&str>>().as_slice() {
+        [name, ticker, total_supply, holder] => Ok(TokenSpec {
+            name: name.to_string(),
+            ticker: ticker.to_string(),
+            total_supply: total_supply
+                .parse()
+                .map_err(|e| format!("invalid total supply {:?}: {}", total_supply, e))?,
+            holder: parse_accountid32(holder)
+                .map_err(|e| format!("invalid holder {:?}: {}", holder, e))?,
+            allocations: vec![],
+        }),
+        _ => Err("token should be formatted as <name>,<ticker>,<total-supply>,<holder>".into()),
+    }
+}
+
+/// Check tokens against the limits enforced by `erc20::init` and make sure the allocations of
+/// each token fit within its supply.
+pub fn check_tokens(tokens: &[TokenSpec]) -> Result<(), String> {
+    for token in tokens {
+        if token.name.len() > MAX_NAME_LEN {
+            return Err(format!(
+                "token name {:?} is longer than {} bytes",
+                token.name, MAX_NAME_LEN
+            ));
+        }
+        if token.ticker.len() > MAX_TICKER_LEN {
+            return Err(format!(
+                "token ticker {:?} is longer than {} bytes",
+                token.ticker, MAX_TICKER_LEN
+            ));
+        }
+        let mut accounts = BTreeSet::new();
+        let mut allocated: u128 = 0;
+        for allocation in &token.allocations {
+            if allocation.account == token.holder {
+                return Err(format!(
+                    "{} holds {} and can not also be allocated it",
+                    allocation.account, token.ticker
+                ));
+            }
+            if !accounts.insert(&allocation.account) {
+                return Err(format!(
+                    "{} is allocated {} more than once",
+                    allocation.account, token.ticker
+                ));
+            }
+            allocated = allocated
+                .checked_add(allocation.amount)
+                .filter(|allocated| *allocated <= token.total_supply)
+                .ok_or_else(|| {
+                    format!(
+                        "allocations of {} exceed its total supply of {}",
+                        token.ticker, token.total_supply
+                    )
+                })?;
+        }
+    }
+    Ok(())
+}
+
+/// Token ids are assigned in order, starting at 0.
+pub fn erc20_genesis(tokens: &[TokenSpec]) -> Erc20Config {
+    Erc20Config {
+        initial_tokens: tokens
+            .iter()
+            .map(|token| {
+                (
+                    Erc20Token {
+                        name: token.name.as_bytes().to_vec(),
+                        ticker: token.ticker.as_bytes().to_vec(),
+                        total_supply: token.total_supply,
+                    },
+                    token.holder.clone(),
+                )
+            })
+            .collect(),
+        initial_allocations: tokens
+            .iter()
+            .enumerate()
+            .flat_map(|(id, token)| {
+                token
+                    .allocations
+                    .iter()
+                    .map(move |a| (id as u32, a.account.clone(), a.amount))
+            })
+            .collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain_spec::tests::VALID_PK;
+
+    #[test]
+    fn t_check_tokens_limits() {
+        let token = |name: &str, ticker: &str| TokenSpec {
+            name: name.to_string(),
+            ticker: ticker.to_string(),
+            ..parse_token(&format!("Dock Dollar,DD,100,{}", VALID_PK)).unwrap()
+        };
+        let name = "n".repeat(MAX_NAME_LEN);
+        let ticker = "t".repeat(MAX_TICKER_LEN);
+        check_tokens(&[token(&name, &ticker)]).unwrap();
+        check_tokens(&[token(&format!("{}n", name), &ticker)]).unwrap_err();
+        check_tokens(&[token(&name, &format!("{}t", ticker))]).unwrap_err();
+    }
+
+    #[test]
+    fn t_token_allocations() {
+        let pk = VALID_PK;
+        let alice = "0xd43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d";
+        let bob = "0x8eaf04151687736326c9fea17e25fc5287613693c912909cb226aa4794f26a48";
+        let token = |allocations: &[(&str, u128)]| TokenSpec {
+            allocations: allocations
+                .iter()
+                .map(|(account, amount)| Allocation {
+                    account: parse_accountid32(account).unwrap(),
+                    amount: *amount,
+                })
+                .collect(),
+            ..parse_token(&format!("Dock Dollar,DD,100,{}", pk)).unwrap()
+        };
+
+        check_tokens(&[token(&[])]).unwrap();
+        check_tokens(&[token(&[(alice, 50), (bob, 50)])]).unwrap();
+        check_tokens(&[token(&[(alice, 50), (bob, 51)])]).unwrap_err();
+        check_tokens(&[token(&[(alice, 1), (alice, 1)])]).unwrap_err();
+        check_tokens(&[token(&[(alice, u128::max_value()), (bob, 1)])]).unwrap_err();
+        check_tokens(&[token(&[(pk, 1)])]).unwrap_err();
+
+        let genesis = erc20_genesis(&[token(&[]), token(&[(alice, 50), (bob, 50)])]);
+        assert_eq!(genesis.initial_tokens.len(), 2);
+        assert_eq!(
+            genesis.initial_allocations,
+            vec![
+                (1, parse_accountid32(alice).unwrap(), 50),
+                (1, parse_accountid32(bob).unwrap(), 50)
+            ]
+        );
+    }
+
+    #[test]
+    fn t_erc20_genesis_without_allocations() {
+        let genesis: Erc20Config = serde_json::from_str(r#"{ "initialTokens": [] }"#).unwrap();
+        assert_eq!(genesis.initial_allocations, vec![]);
+    }
+
+    #[test]
+    fn t_parse_token() {
+        let pk = VALID_PK;
+        let token = parse_token(&format!("Dock Dollar,DD,100,{}", pk)).unwrap();
+        assert_eq!(token.name, "Dock Dollar");
+        assert_eq!(token.ticker, "DD");
+        assert_eq!(token.total_supply, 100);
+        for invalid in &[
+            "Dock Dollar,DD,100".to_string(),
+            format!("Dock Dollar,DD,-1,{}", pk),
+            format!("Dock Dollar,DD,100,{},", pk),
+            "Dock Dollar,DD,100,DD".to_string(),
+        ] {
+            parse_token(invalid).unwrap_err();
+        }
+    }
+}
@@ -0,0 +1,296 @@
+//! Last look at a genesis config before it is written to a chainspec. Catches genesis states the
+//! runtime would panic on while building storage, or that would leave the chain stuck.
+
+use crate::allocation::check_balances;
+use crate::validator::check_unique_keys;
+use crate::vesting::{check_vesting, Vesting};
+use node_template_runtime::{ExistentialDeposit, GenesisConfig};
+use sr_primitives::AccountId32;
+use srml_support::traits::Get;
+use std::collections::BTreeSet;
+
+/// Largest total issuance, or erc20 total supply, accepted at genesis. No single balance can
+/// exceed the total, so credits only overflow once the total itself no longer fits in a u128.
+/// Genesis may use at most half of that range; the other half is left for issuance added later,
+/// such as the balance `client fork --dev` gives Alice or a minting module added by a runtime
+/// upgrade, so the supply can at least double before a `checked_add` starts failing.
+pub const MAX_SUPPLY: u128 = u128::max_value() / 2;
+
+/// Check a genesis config, reporting every problem found rather than just the first.
+pub fn validate_genesis(genesis: &GenesisConfig) -> Result<(), String> {
+    let mut problems = authorities(genesis);
+    problems.extend(balances(genesis));
+    problems.extend(tokens(genesis));
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(format!("invalid genesis:\n  - {}", problems.join("\n  - ")))
+    }
+}
+
+fn authorities(genesis: &GenesisConfig) -> Vec<String> {
+    let mut problems = Vec::new();
+    let babe: Vec<_> = genesis
+        .babe
+        .iter()
+        .flat_map(|babe| babe.authorities.iter().map(|(id, _)| id))
+        .collect();
+    let grandpa: Vec<_> = genesis
+        .grandpa
+        .iter()
+        .flat_map(|grandpa| grandpa.authorities.iter().map(|(id, _)| id))
+        .collect();
+    if babe.is_empty() {
+        problems.push("there are no babe authorities, no blocks would be produced".to_string());
+    }
+    if grandpa.is_empty() {
+        problems.push("there are no grandpa authorities, no blocks would be finalized".to_string());
+    }
+    problems.extend(check_unique_keys(grandpa, babe).err());
+    problems
+}
+
+fn balances(genesis: &GenesisConfig) -> Vec<String> {
+    let mut problems = Vec::new();
+    let (balances, vesting) = match &genesis.balances {
+        Some(config) => (config.balances.as_slice(), config.vesting.as_slice()),
+        None => (&[][..], &[][..]),
+    };
+
+    let vesting: Vec<Vesting> = vesting
+        .iter()
+        .map(|(account, start, length, liquid)| Vesting {
+            account: account.clone(),
+            start: *start,
+            length: *length,
+            liquid: *liquid,
+        })
+        .collect();
+    problems.extend(check_balances(balances).err());
+    problems.extend(check_vesting(&vesting, balances).err());
+
+    let existential_deposit = ExistentialDeposit::get();
+    for (account, amount) in balances {
+        if *amount < existential_deposit {
+            problems.push(format!(
+                "{} is endowed {}, below the existential deposit of {}",
+                account, amount, existential_deposit
+            ));
+        }
+    }
+
+    match balances
+        .iter()
+        .try_fold(0u128, |total, (_, amount)| total.checked_add(*amount))
+    {
+        None => problems.push("total issuance overflows a u128".to_string()),
+        Some(total) if total > MAX_SUPPLY => problems.push(format!(
+            "total issuance of {} exceeds the maximum of {}",
+            total, MAX_SUPPLY
+        )),
+        Some(_) => {}
+    }
+
+    if let Some(sudo) = &genesis.sudo {
+        if balance(balances, &sudo.key) == 0 {
+            problems.push(format!(
+                "sudo key {} has no funds to pay transaction fees",
+                sudo.key
+            ));
+        }
+    }
+    problems
+}
+
+fn tokens(genesis: &GenesisConfig) -> Vec<String> {
+    let mut problems = Vec::new();
+    let (tokens, allocations) = match &genesis.erc20 {
+        Some(config) => (
+            config.initial_tokens.as_slice(),
+            config.initial_allocations.as_slice(),
+        ),
+        None => return problems,
+    };
+
+    for (token, _) in tokens {
+        if token.total_supply > MAX_SUPPLY {
+            problems.push(format!(
+                "total supply of {} is {}, leaving no headroom above the maximum of {}",
+                String::from_utf8_lossy(&token.ticker),
+                token.total_supply,
+                MAX_SUPPLY
+            ));
+        }
+    }
+
+    problems.extend(
+        duplicates(allocations.iter().map(|(id, account, _)| (id, account)))
+            .map(|(id, account)| format!("{} is allocated token {} more than once", account, id)),
+    );
+    let mut allocated = vec![Some(0u128); tokens.len()];
+    for (id, _, amount) in allocations {
+        match allocated.get_mut(*id as usize) {
+            Some(total) => *total = total.and_then(|total| total.checked_add(*amount)),
+            None => problems.push(format!("allocation of token {} which does not exist", id)),
+        }
+    }
+    for ((token, _), allocated) in tokens.iter().zip(allocated) {
+        if allocated.filter(|a| *a <= token.total_supply).is_none() {
+            problems.push(format!(
+                "allocations of {} exceed its total supply of {}",
+                String::from_utf8_lossy(&token.ticker),
+                token.total_supply
+            ));
+        }
+    }
+    problems
+}
+
+fn balance(balances: &[(AccountId32, u128)], account: &AccountId32) -> u128 {
+    balances
+        .iter()
+        .filter(|(a, _)| a == account)
+        .fold(0, |total, (_, amount)| total.saturating_add(*amount))
+}
+
+/// Items that appear more than once, each reported once.
+fn duplicates<T: Ord>(items: impl IntoIterator<Item = T>) -> impl Iterator<Item = T> {
+    let mut seen = BTreeSet::new();
+    let mut repeated = BTreeSet::new();
+    for item in items {
+        if seen.contains(&item) {
+            repeated.insert(item);
+        } else {
+            seen.insert(item);
+        }
+    }
+    repeated.into_iter()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain_spec::Chain;
+    use node_template_runtime::Erc20Config;
+    use substrate_warmup_common::DevAccount;
+
+    #[test]
+    fn t_validate_genesis() {
+        let alice = DevAccount::Alice.account_id();
+        let bob = DevAccount::Bob.account_id();
+        let genesis = || {
+            Chain::Ved { wasm: None }
+                .builder()
+                .unwrap()
+                .genesis()
+                .unwrap()
+        };
+        validate_genesis(&genesis()).unwrap();
+
+        fn invalid(
+            mut genesis: GenesisConfig,
+            change: impl FnOnce(&mut GenesisConfig),
+            expected: &str,
+        ) {
+            change(&mut genesis);
+            let err = validate_genesis(&genesis).unwrap_err();
+            assert!(
+                err.contains(expected),
+                "{:?} does not contain {:?}",
+                err,
+                expected
+            );
+        }
+        fn balances(genesis: &mut GenesisConfig) -> &mut Vec<(AccountId32, u128)> {
+            &mut genesis.balances.as_mut().unwrap().balances
+        }
+        fn erc20(genesis: &mut GenesisConfig) -> &mut Erc20Config {
+            genesis.erc20.as_mut().unwrap()
+        }
+
+        invalid(
+            genesis(),
+            |g| balances(g)[0].1 = u128::max_value(),
+            "total issuance of 340282366920938463463374607431768211455 exceeds",
+        );
+        invalid(
+            genesis(),
+            |g| balances(g).push((bob.clone(), u128::max_value())),
+            "total issuance overflows",
+        );
+        invalid(
+            genesis(),
+            |g| balances(g).push((alice.clone(), 1)),
+            "is endowed more than once",
+        );
+        invalid(
+            genesis(),
+            |g| balances(g).push((bob.clone(), 0)),
+            "below the existential deposit of 1",
+        );
+        invalid(
+            genesis(),
+            |g| g.sudo.as_mut().unwrap().key = bob.clone(),
+            "has no funds to pay transaction fees",
+        );
+        invalid(
+            genesis(),
+            |g| g.babe.as_mut().unwrap().authorities.clear(),
+            "no babe authorities",
+        );
+        invalid(
+            genesis(),
+            |g| g.grandpa.as_mut().unwrap().authorities.clear(),
+            "no grandpa authorities",
+        );
+        invalid(
+            genesis(),
+            |g| {
+                let authorities = &mut g.grandpa.as_mut().unwrap().authorities;
+                authorities.push(authorities[0].clone());
+            },
+            "duplicate grandpa key",
+        );
+        invalid(
+            genesis(),
+            |g| {
+                g.balances.as_mut().unwrap().vesting =
+                    vec![(alice.clone(), 0, 1, 0), (alice.clone(), 0, 1, 0)];
+            },
+            "has more than one vesting schedule",
+        );
+        invalid(
+            genesis(),
+            |g| erc20(g).initial_tokens[1].0.total_supply = u128::max_value(),
+            "total supply of PSTABLE2 is 340282366920938463463374607431768211455, leaving no \
+             headroom",
+        );
+        invalid(
+            genesis(),
+            |g| {
+                erc20(g).initial_allocations = vec![(0, bob.clone(), 1), (0, bob.clone(), 1)];
+            },
+            "is allocated token 0 more than once",
+        );
+        invalid(
+            genesis(),
+            |g| erc20(g).initial_allocations = vec![(2, bob.clone(), 1)],
+            "allocation of token 2 which does not exist",
+        );
+        invalid(
+            genesis(),
+            |g| {
+                let supply = erc20(g).initial_tokens[0].0.total_supply;
+                erc20(g).initial_allocations = vec![(0, bob.clone(), supply + 1)];
+            },
+            "allocations of PSTABLE1 exceed its total supply",
+        );
+
+        // every problem is reported
+        let mut broken = genesis();
+        broken.babe.as_mut().unwrap().authorities.clear();
+        broken.sudo.as_mut().unwrap().key = bob;
+        let err = validate_genesis(&broken).unwrap_err();
+        assert_eq!(err.lines().count(), 3, "{}", err);
+    }
+}
@@ -0,0 +1,151 @@
+use crate::config::public;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
+use substrate_consensus_babe_primitives::AuthorityId as BabeId;
+use substrate_finality_grandpa_primitives::AuthorityId as GrandpaId;
+use substrate_warmup_common::parse_pubkey;
+
+/// A genesis authority. The same weight is used for both block production (babe) and
+/// finalization (grandpa).
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Validator {
+    #[serde(deserialize_with = "public")]
+    pub grandpa: GrandpaId,
+    #[serde(deserialize_with = "public")]
+    pub babe: BabeId,
+    /// Babe and grandpa weight, defaults to 1.
+    #[serde(default = "default_weight")]
+    pub weight: u64,
+}
+
+fn default_weight() -> u64 {
+    1
+}
+
+impl Validator {
+    pub fn new(grandpa: GrandpaId, babe: BabeId) -> Self {
+        Self {
+            grandpa,
+            babe,
+            weight: default_weight(),
+        }
+    }
+}
+
+/// parse a validator from `<grandpa-key>,<babe-key>[,<weight>]`, weight defaults to 1
+pub fn parse_validator(imp: &str) -> Result<Validator, String> {
+    let parts: Vec<&str> = imp.split(',').collect();
+    let (grandpa, babe, weight) = match parts.as_slice() {
+        [grandpa, babe] => (grandpa, babe, default_weight()),
+        [grandpa, babe, weight] => (
+            grandpa,
+            babe,
+            weight
+                .parse()
+                .map_err(|e| format!("invalid validator weight {:?}: {}", weight, e))?,
+        ),
+        _ => return Err("validator should be formatted as <grandpa>,<babe>[,<weight>]".into()),
+    };
+    Ok(Validator {
+        grandpa: parse_pubkey(grandpa)
+            .map_err(|e| format!("invalid grandpa key {:?}: {}", grandpa, e))?,
+        babe: parse_pubkey(babe).map_err(|e| format!("invalid babe key {:?}: {}", babe, e))?,
+        weight,
+    })
+}
+
+/// Reject validator sets that would produce a broken or ambiguous genesis: an empty set, zero
+/// weights, or a babe or grandpa key that appears more than once.
+pub fn check_validators(validators: &[Validator]) -> Result<(), String> {
+    if validators.is_empty() {
+        return Err("at least one validator is required".into());
+    }
+    if let Some(validator) = validators.iter().find(|validator| validator.weight == 0) {
+        return Err(format!(
+            "validator with grandpa key {} has zero weight",
+            validator.grandpa
+        ));
+    }
+    check_unique_keys(
+        validators.iter().map(|validator| &validator.grandpa),
+        validators.iter().map(|validator| &validator.babe),
+    )
+}
+
+/// Reject a grandpa or babe key that appears more than once.
+pub fn check_unique_keys<'a>(
+    grandpa: impl IntoIterator<Item = &'a GrandpaId>,
+    babe: impl IntoIterator<Item = &'a BabeId>,
+) -> Result<(), String> {
+    let mut grandpa_keys = BTreeSet::new();
+    for key in grandpa {
+        if !grandpa_keys.insert(key) {
+            return Err(format!("duplicate grandpa key {}", key));
+        }
+    }
+    let mut babe_keys = BTreeSet::new();
+    for key in babe {
+        if !babe_keys.insert(key) {
+            return Err(format!("duplicate babe key {}", key));
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain_spec::dev_validator;
+    use crate::chain_spec::tests::VALID_PK;
+    use substrate_warmup_common::DevAccount;
+
+    #[test]
+    fn t_parse_validator() {
+        let pk = VALID_PK;
+        assert_eq!(
+            parse_validator(&format!("{},{}", pk, pk)).unwrap().weight,
+            1
+        );
+        assert_eq!(
+            parse_validator(&format!("{},{},7", pk, pk)).unwrap().weight,
+            7
+        );
+        for invalid in &[
+            pk.to_string(),
+            format!("{},{},", pk, pk),
+            format!("{},{},-1", pk, pk),
+            format!("{},{},1,1", pk, pk),
+            format!("{},0x00", pk),
+        ] {
+            parse_validator(invalid).unwrap_err();
+        }
+    }
+
+    #[test]
+    fn t_check_validators() {
+        let alice = dev_validator(DevAccount::Alice);
+        let bob = dev_validator(DevAccount::Bob);
+        check_validators(&[alice.clone(), bob.clone()]).unwrap();
+        check_validators(&[]).unwrap_err();
+
+        // zero weight
+        let weightless = Validator {
+            weight: 0,
+            ..bob.clone()
+        };
+        check_validators(&[alice.clone(), weightless]).unwrap_err();
+
+        // duplicate keys
+        let same_grandpa = Validator {
+            grandpa: alice.grandpa.clone(),
+            ..bob.clone()
+        };
+        check_validators(&[alice.clone(), same_grandpa]).unwrap_err();
+        let same_babe = Validator {
+            babe: alice.babe.clone(),
+            ..bob
+        };
+        check_validators(&[alice, same_babe]).unwrap_err();
+    }
+}
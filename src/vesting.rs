@@ -0,0 +1,148 @@
+use crate::config::{account, amount};
+use serde::{Deserialize, Serialize};
+use sr_primitives::AccountId32;
+use std::collections::BTreeSet;
+use substrate_warmup_common::parse_accountid32;
+
+/// Linear vesting of an endowed account's native balance. Everything but `liquid` is locked at
+/// genesis and unlocks evenly over `length` blocks starting at block `start`.
+#[derive(Clone, Eq, PartialEq, Ord, PartialOrd, Debug, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Vesting {
+    #[serde(deserialize_with = "account")]
+    pub account: AccountId32,
+    pub start: u32,
+    pub length: u32,
+    #[serde(deserialize_with = "amount")]
+    pub liquid: u128,
+}
+
+/// parse a vesting schedule from `<account>,<start-block>,<length-in-blocks>,<liquid-amount>`
+pub fn parse_vesting(imp: &str) -> Result<Vesting, String> {
+    match imp.split(',').collect::<Vec<&str>>().as_slice() {
+        [account, start, length, liquid] => Ok(Vesting {
+            account: parse_accountid32(account)
+                .map_err(|e| format!("invalid account {:?}: {}", account, e))?,
+            start: start
+                .parse()
+                .map_err(|e| format!("invalid start block {:?}: {}", start, e))?,
+            length: length
+                .parse()
+                .map_err(|e| format!("invalid vesting length {:?}: {}", length, e))?,
+            liquid: liquid
+                .parse()
+                .map_err(|e| format!("invalid liquid amount {:?}: {}", liquid, e))?,
+        }),
+        _ => Err("vesting should be formatted as <account>,<start>,<length>,<liquid>".into()),
+    }
+}
+
+/// The balances module computes the locked amount as endowment minus liquid amount, divided over
+/// the length of the schedule. Make sure that works out for every schedule. The amount unlocked per
+/// block is rounded down, so a locked amount that isn't a multiple of the length would leave a
+/// remainder locked forever.
+pub fn check_vesting(vesting: &[Vesting], endowed: &[(AccountId32, u128)]) -> Result<(), String> {
+    let mut accounts = BTreeSet::new();
+    for schedule in vesting {
+        if !accounts.insert(&schedule.account) {
+            return Err(format!(
+                "{} has more than one vesting schedule",
+                schedule.account
+            ));
+        }
+        if schedule.length == 0 {
+            return Err(format!(
+                "vesting schedule of {} has zero length",
+                schedule.account
+            ));
+        }
+        let endowment = endowed
+            .iter()
+            .find(|(account, _)| *account == schedule.account)
+            .map(|(_, amount)| *amount)
+            .ok_or_else(|| format!("{} is vested but not endowed", schedule.account))?;
+        if schedule.liquid > endowment {
+            return Err(format!(
+                "liquid amount {} of {} exceeds its endowment of {}",
+                schedule.liquid, schedule.account, endowment
+            ));
+        }
+        let locked = endowment - schedule.liquid;
+        if locked % u128::from(schedule.length) != 0 {
+            return Err(format!(
+                "locked amount {} of {} is not a multiple of the vesting length {}, the remainder \
+                 would never unlock",
+                locked, schedule.account, schedule.length
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// Format expected by `BalancesConfig::vesting`.
+pub fn balances_vesting(vesting: &[Vesting]) -> Vec<(AccountId32, u32, u32, u128)> {
+    vesting
+        .iter()
+        .map(|v| (v.account.clone(), v.start, v.length, v.liquid))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use substrate_warmup_common::DevAccount;
+
+    #[test]
+    fn t_parse_vesting() {
+        let vesting = parse_vesting("//Alice,10,100,5").unwrap();
+        assert_eq!(
+            vesting,
+            Vesting {
+                account: DevAccount::Alice.account_id(),
+                start: 10,
+                length: 100,
+                liquid: 5,
+            }
+        );
+        for invalid in &[
+            "//Alice,10,100",
+            "//Alice,10,100,5,",
+            "//Alice,-10,100,5",
+            "//Alice,10,100,five",
+            "Alice,10,100,5",
+        ] {
+            parse_vesting(invalid).unwrap_err();
+        }
+    }
+
+    #[test]
+    fn t_check_vesting() {
+        let alice = DevAccount::Alice.account_id();
+        let bob = DevAccount::Bob.account_id();
+        let schedule = |account: &AccountId32, liquid, length| Vesting {
+            account: account.clone(),
+            start: 0,
+            length,
+            liquid,
+        };
+        let endowed = [(alice.clone(), 1000), (bob.clone(), 10)];
+
+        check_vesting(&[schedule(&alice, 0, 100), schedule(&bob, 10, 1)], &endowed).unwrap();
+        check_vesting(&[schedule(&bob, 4, 3)], &endowed).unwrap();
+        // liquid amount exceeds the endowment
+        check_vesting(&[schedule(&bob, 11, 1)], &endowed).unwrap_err();
+        // locked amount is not a multiple of the length
+        check_vesting(&[schedule(&bob, 5, 3)], &endowed).unwrap_err();
+        // zero length
+        check_vesting(&[schedule(&alice, 0, 0)], &endowed).unwrap_err();
+        // not endowed
+        let charlie = DevAccount::Charlie.account_id();
+        check_vesting(&[schedule(&charlie, 0, 1)], &endowed).unwrap_err();
+        // more than one schedule
+        check_vesting(
+            &[schedule(&alice, 0, 100), schedule(&alice, 500, 100)],
+            &endowed,
+        )
+        .unwrap_err();
+    }
+}
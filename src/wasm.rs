@@ -0,0 +1,100 @@
+//! Runtime wasm blobs read from disk, for embedding a released runtime in a chainspec instead of
+//! the one compiled into chaingen.
+
+use node_template_runtime::Version;
+use parity_scale_codec::Decode;
+use sr_io::SubstrateHostFunctions;
+use sr_version::RuntimeVersion;
+use srml_support::traits::Get;
+use std::path::Path;
+use substrate_executor::{call_in_wasm, WasmExecutionMethod};
+use substrate_state_machine::BasicExternalities;
+
+/// Heap pages given to the runtime while it reports its version, the default used by nodes.
+const HEAP_PAGES: u64 = 1024;
+
+/// Ask a runtime for its version by calling `Core_version`.
+pub fn runtime_version(code: &[u8]) -> Result<RuntimeVersion, String> {
+    let mut ext = BasicExternalities::default();
+    let encoded = call_in_wasm::<_, SubstrateHostFunctions>(
+        "Core_version",
+        &[],
+        WasmExecutionMethod::Interpreted,
+        &mut ext,
+        code,
+        HEAP_PAGES,
+    )
+    .map_err(|e| format!("could not read the runtime version: {}", e))?;
+    RuntimeVersion::decode(&mut &encoded[..])
+        .map_err(|e| format!("could not decode the runtime version: {}", e.what()))
+}
+
+/// Read a runtime wasm blob, refusing runtimes other than the one chaingen builds genesis for.
+pub fn load_wasm(path: &Path) -> Result<Vec<u8>, String> {
+    let code =
+        std::fs::read(path).map_err(|e| format!("could not read {}: {}", path.display(), e))?;
+    let version = runtime_version(&code).map_err(|e| format!("{}: {}", path.display(), e))?;
+    let expected = Version::get().spec_name;
+    if version.spec_name != expected {
+        return Err(format!(
+            "{} is a {:?} runtime, expected {:?}",
+            path.display(),
+            version.spec_name.to_string(),
+            expected.to_string()
+        ));
+    }
+    Ok(code)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chain_spec::tests::temp_file;
+    use node_template_runtime::WASM_BINARY;
+
+    #[test]
+    fn t_runtime_version() {
+        let version = runtime_version(WASM_BINARY).unwrap();
+        assert_eq!(version.spec_name, Version::get().spec_name);
+        assert_eq!(version.spec_version, Version::get().spec_version);
+        runtime_version(b"not wasm").unwrap_err();
+    }
+
+    #[test]
+    fn t_load_wasm() {
+        let path = temp_file("t_load_wasm.wasm", WASM_BINARY);
+        assert_eq!(load_wasm(&path).unwrap(), WASM_BINARY);
+        std::fs::write(&path, b"not wasm").unwrap();
+        load_wasm(&path).unwrap_err();
+        std::fs::remove_file(path).unwrap();
+        load_wasm(Path::new("this/file/does/not/exist.wasm")).unwrap_err();
+    }
+
+    #[test]
+    fn t_load_wasm_wrong_spec_name() {
+        // rename the runtime by patching its name, keeping the length, wherever it is stored
+        let name = b"node-template";
+        let mut code = WASM_BINARY.to_vec();
+        let mut renamed = 0;
+        for i in 0..=code.len() - name.len() {
+            if &code[i..i + name.len()] == name {
+                code[i + name.len() - 1] = b'x';
+                renamed += 1;
+            }
+        }
+        assert!(renamed > 0);
+        assert_eq!(
+            runtime_version(&code).unwrap().spec_name.to_string(),
+            "node-templatx"
+        );
+
+        let path = temp_file("t_load_wasm_wrong_spec_name.wasm", &code);
+        let err = load_wasm(&path).unwrap_err();
+        std::fs::remove_file(path).unwrap();
+        assert!(
+            err.ends_with("is a \"node-templatx\" runtime, expected \"node-template\""),
+            "{}",
+            err
+        );
+    }
+}